@@ -5121,6 +5121,7 @@ fn invoked_slash_command_fold_placeholder(
                 .into_any_element()
         }),
         type_tag: Some(TypeId::of::<PendingSlashCommand>()),
+        ..Default::default()
     }
 }
 
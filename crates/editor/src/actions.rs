@@ -149,6 +149,36 @@ pub struct ShowCompletions {
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
 pub struct HandleInput(pub String);
 
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct SortLinesNumeric {
+    /// Whether lines that don't contain a number are placed after the ones that do.
+    #[serde(default)]
+    pub numberless_lines_last: bool,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct SurroundSelections {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct AlignCursors {
+    /// When set, each cursor is first moved to the next occurrence of this
+    /// character on its line, so that the characters end up aligned.
+    #[serde(default)]
+    pub character: Option<char>,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct SelectInsideDelimiters {
+    pub open: char,
+    pub close: char,
+    /// Whether the delimiters themselves are included in the selection.
+    #[serde(default)]
+    pub include_delimiters: bool,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
 pub struct DeleteToNextWordEnd {
     #[serde(default)]
@@ -172,6 +202,49 @@ pub struct SpawnNearestTask {
     pub reveal: task::RevealStrategy,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct GoToDiagnostic {
+    /// The least severe diagnostics to stop at. Defaults to the editor's
+    /// diagnostic severity filter.
+    #[serde(default)]
+    pub severity: Option<DiagnosticSeverityFilter>,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema)]
+pub struct GoToPrevDiagnostic {
+    /// The least severe diagnostics to stop at. Defaults to the editor's
+    /// diagnostic severity filter.
+    #[serde(default)]
+    pub severity: Option<DiagnosticSeverityFilter>,
+}
+
+/// Which diagnostics are visited when navigating between diagnostics.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverityFilter {
+    /// Only errors.
+    Error,
+    /// Errors and warnings.
+    #[default]
+    Warning,
+    /// Errors, warnings and information.
+    Information,
+    /// All diagnostics, including hints.
+    Hint,
+}
+
+impl DiagnosticSeverityFilter {
+    pub fn includes(self, severity: DiagnosticSeverity) -> bool {
+        let least_severe = match self {
+            Self::Error => DiagnosticSeverity::ERROR,
+            Self::Warning => DiagnosticSeverity::WARNING,
+            Self::Information => DiagnosticSeverity::INFORMATION,
+            Self::Hint => DiagnosticSeverity::HINT,
+        };
+        severity <= least_severe
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Default)]
 pub enum UuidVersion {
     #[default]
@@ -182,6 +255,7 @@ pub enum UuidVersion {
 impl_actions!(
     editor,
     [
+        AlignCursors,
         ComposeCompletion,
         ConfirmCodeAction,
         ConfirmCompletion,
@@ -191,6 +265,8 @@ impl_actions!(
         ExpandExcerptsDown,
         ExpandExcerptsUp,
         FoldAt,
+        GoToDiagnostic,
+        GoToPrevDiagnostic,
         HandleInput,
         MoveDownByLines,
         MovePageDown,
@@ -199,13 +275,16 @@ impl_actions!(
         MoveToEndOfLine,
         MoveUpByLines,
         SelectDownByLines,
+        SelectInsideDelimiters,
         SelectNext,
         SelectPrevious,
         SelectToBeginningOfLine,
         SelectToEndOfLine,
         SelectUpByLines,
+        SortLinesNumeric,
         SpawnNearestTask,
         ShowCompletions,
+        SurroundSelections,
         ToggleCodeActions,
         ToggleComments,
         UnfoldAt,
@@ -242,6 +321,7 @@ gpui::actions!(
         Copy,
         CopyFileLocation,
         CopyHighlightJson,
+        CopyIndentFromAboveLine,
         CopyPath,
         CopyPermalinkToLine,
         CopyRelativePath,
@@ -259,6 +339,7 @@ gpui::actions!(
         DuplicateSelection,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
+        ExpandToLine,
         FindAllReferences,
         Fold,
         FoldAll,
@@ -273,12 +354,12 @@ gpui::actions!(
         GoToDeclarationSplit,
         GoToDefinition,
         GoToDefinitionSplit,
-        GoToDiagnostic,
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
-        GoToPrevDiagnostic,
+        GoToNextChange,
         GoToPrevHunk,
+        GoToPreviousChange,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
         HalfPageDown,
@@ -287,6 +368,7 @@ gpui::actions!(
         Indent,
         InsertUuidV4,
         InsertUuidV7,
+        InvertSelections,
         JoinLines,
         KillRingCut,
         KillRingYank,
@@ -342,6 +424,7 @@ gpui::actions!(
         SelectAll,
         SelectAllMatches,
         SelectDown,
+        SelectEnclosingScope,
         SelectEnclosingSymbol,
         SelectLargerSyntaxNode,
         SelectLeft,
@@ -351,6 +434,7 @@ gpui::actions!(
         SelectRight,
         SelectSmallerSyntaxNode,
         SelectToBeginning,
+        SelectToEnclosingBracket,
         SelectToEnd,
         SelectToEndOfParagraph,
         SelectToNextSubwordEnd,
@@ -362,6 +446,7 @@ gpui::actions!(
         ShowCharacterPalette,
         ShowInlineCompletion,
         ShowSignatureHelp,
+        ShrinkEnclosingScope,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
@@ -370,8 +455,11 @@ gpui::actions!(
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
+        ToggleCase,
+        ToggleChangeMarkers,
         ToggleGitBlame,
         ToggleGitBlameInline,
+        ToggleDiagnosticAtCursor,
         ToggleHunkDiff,
         ToggleIndentGuides,
         ToggleInlayHints,
@@ -1139,6 +1139,64 @@ impl DisplaySnapshot {
         self.text_chunks(DisplayRow(0)).collect()
     }
 
+    /// Returns the buffer text spanned by the given display range, leaving out
+    /// any text hidden by folds. When `include_fold_placeholders` is true, each
+    /// fold is represented by its placeholder text instead.
+    pub fn text_for_display_range(
+        &self,
+        range: Range<DisplayPoint>,
+        include_fold_placeholders: bool,
+    ) -> String {
+        let buffer = &self.buffer_snapshot;
+        let start = self
+            .clip_point(range.start, Bias::Left)
+            .to_offset(self, Bias::Left);
+        let end = self
+            .clip_point(range.end, Bias::Right)
+            .to_offset(self, Bias::Right);
+
+        let mut text = String::new();
+        let mut offset = start;
+        let mut folds = self
+            .folds_in_range(start..end)
+            .map(|fold| {
+                let range = fold.range.start.to_offset(buffer)..fold.range.end.to_offset(buffer);
+                (range, &fold.placeholder)
+            })
+            .peekable();
+        while let Some((mut fold_range, placeholder)) = folds.next() {
+            // Merge folds the same way the fold map does when displaying them.
+            while let Some((next_range, next_placeholder)) = folds.peek() {
+                if next_range.start < fold_range.end
+                    || (next_range.start == fold_range.end
+                        && placeholder.merge_adjacent
+                        && next_placeholder.merge_adjacent)
+                {
+                    fold_range.end = fold_range.end.max(next_range.end);
+                    folds.next();
+                } else {
+                    break;
+                }
+            }
+
+            if fold_range.end <= offset || fold_range.start >= end {
+                continue;
+            }
+
+            if fold_range.start > offset {
+                text.extend(buffer.text_for_range(offset..fold_range.start));
+            }
+            if include_fold_placeholders {
+                text.push_str(placeholder.text.as_deref().unwrap_or("⋯"));
+            }
+            offset = fold_range.end;
+        }
+        if offset < end {
+            text.extend(buffer.text_for_range(offset..end));
+        }
+        text
+    }
+
     pub fn line(&self, display_row: DisplayRow) -> String {
         let mut result = String::new();
         for chunk in self.text_chunks(display_row) {
@@ -1284,6 +1342,68 @@ impl DisplaySnapshot {
         }
     }
 
+    /// Returns a crease for the smallest syntax node that encloses `point` and
+    /// spans multiple lines. The crease starts at the end of the node's first
+    /// line, so that the code introducing the node stays visible, and stops
+    /// before the node's closing bracket, if it has one.
+    pub fn syntax_crease_for_point(&self, point: Point) -> Option<Crease<Point>> {
+        let buffer = &self.buffer_snapshot;
+        let offset = point.to_offset(buffer);
+        let mut range = offset..offset;
+        let (start, end) = loop {
+            let (_, node_range) = buffer.syntax_ancestor(range.clone())?;
+            let start = node_range.start.to_point(buffer);
+            let end = node_range.end.to_point(buffer);
+            if start.row < end.row {
+                break (start, node_range.end);
+            }
+            range = node_range;
+        };
+
+        let fold_start = Point::new(start.row, buffer.line_len(MultiBufferRow(start.row)));
+        let fold_end = match buffer.reversed_chars_at(end).next() {
+            Some(')' | ']' | '}') => (end - 1).to_point(buffer),
+            _ => end.to_point(buffer),
+        };
+        if fold_end <= fold_start {
+            return None;
+        }
+
+        Some(Crease::simple(
+            fold_start..fold_end,
+            self.fold_placeholder.clone(),
+        ))
+    }
+
+    /// Returns a crease for the given row, preferring the syntax tree over
+    /// indentation. Creases inserted into the crease map take precedence; when
+    /// the row's language has a grammar, the node beginning on the row is used
+    /// instead of the indentation-based crease.
+    pub fn syntax_crease_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Crease<Point>> {
+        if self
+            .crease_snapshot
+            .query_row(buffer_row, &self.buffer_snapshot)
+            .is_some()
+        {
+            return self.crease_for_buffer_row(buffer_row);
+        }
+
+        let line_end = Point::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        let has_grammar = self
+            .buffer_snapshot
+            .language_at(line_end)
+            .map_or(false, |language| language.grammar().is_some());
+        if has_grammar {
+            self.syntax_crease_for_point(line_end)
+                .filter(|crease| crease.range().start.row == buffer_row.0)
+        } else {
+            self.crease_for_buffer_row(buffer_row)
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn text_highlight_ranges<Tag: ?Sized + 'static>(
         &self,
@@ -2577,6 +2697,69 @@ pub mod tests {
         assert("   ˇˇ\t", false, Right, cx);
     }
 
+    #[gpui::test]
+    fn test_text_for_display_range(cx: &mut gpui::AppContext) {
+        init_test(cx, |_| {});
+
+        let text = "aaa\nbbb\nccc\nddd\neee";
+        let buffer = MultiBuffer::build_simple(text, cx);
+        let font_size = px(14.0);
+        let map = cx.new_model(|cx| {
+            DisplayMap::new(
+                buffer.clone(),
+                font("Helvetica"),
+                font_size,
+                None,
+                true,
+                1,
+                1,
+                0,
+                FoldPlaceholder::test(),
+                cx,
+            )
+        });
+        map.update(cx, |map, cx| {
+            map.fold(
+                vec![
+                    Crease::simple(
+                        MultiBufferPoint::new(0, 1)..MultiBufferPoint::new(1, 2),
+                        FoldPlaceholder::test(),
+                    ),
+                    Crease::simple(
+                        MultiBufferPoint::new(2, 2)..MultiBufferPoint::new(3, 1),
+                        FoldPlaceholder {
+                            text: Some("…".into()),
+                            ..FoldPlaceholder::test()
+                        },
+                    ),
+                ],
+                cx,
+            )
+        });
+
+        let snapshot = map.update(cx, |map, cx| map.snapshot(cx));
+        assert_eq!(snapshot.text(), "a⋯b\ncc…dd\neee");
+
+        let range = DisplayPoint::new(DisplayRow(0), 0)..DisplayPoint::new(DisplayRow(2), 3);
+        assert_eq!(
+            snapshot.text_for_display_range(range.clone(), false),
+            "ab\nccdd\neee"
+        );
+        assert_eq!(
+            snapshot.text_for_display_range(range, true),
+            "a⋯b\ncc…dd\neee"
+        );
+
+        // A range that ends within a fold's placeholder includes the whole fold.
+        let range = DisplayPoint::new(DisplayRow(1), 1)..DisplayPoint::new(DisplayRow(1), 3);
+        assert_eq!(snapshot.text_for_display_range(range.clone(), false), "c");
+        assert_eq!(snapshot.text_for_display_range(range, true), "c…");
+
+        // Ranges that don't intersect any folds return the buffer text.
+        let range = DisplayPoint::new(DisplayRow(1), 6)..DisplayPoint::new(DisplayRow(2), 2);
+        assert_eq!(snapshot.text_for_display_range(range, true), "d\nee");
+    }
+
     #[gpui::test]
     fn test_clip_at_line_ends(cx: &mut gpui::AppContext) {
         init_test(cx, |_| {});
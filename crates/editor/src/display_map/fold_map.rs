@@ -2,7 +2,7 @@ use super::{
     inlay_map::{InlayBufferRows, InlayChunks, InlayEdit, InlayOffset, InlayPoint, InlaySnapshot},
     Highlights,
 };
-use gpui::{AnyElement, ElementId, WindowContext};
+use gpui::{AnyElement, ElementId, SharedString, WindowContext};
 use language::{Chunk, ChunkRenderer, Edit, Point, TextSummary};
 use multi_buffer::{Anchor, AnchorRangeExt, MultiBufferRow, MultiBufferSnapshot, ToOffset};
 use std::{
@@ -26,6 +26,9 @@ pub struct FoldPlaceholder {
     pub merge_adjacent: bool,
     /// Category of the fold. Useful for carefully removing from overlapping folds.
     pub type_tag: Option<TypeId>,
+    /// The text that takes the folded range's place in the display map. An
+    /// ellipsis is used when this is `None`.
+    pub text: Option<SharedString>,
 }
 
 impl Default for FoldPlaceholder {
@@ -35,6 +38,7 @@ impl Default for FoldPlaceholder {
             constrain_width: true,
             merge_adjacent: true,
             type_tag: None,
+            text: None,
         }
     }
 }
@@ -47,6 +51,7 @@ impl FoldPlaceholder {
             constrain_width: true,
             merge_adjacent: true,
             type_tag: None,
+            text: None,
         }
     }
 }
@@ -55,6 +60,7 @@ impl fmt::Debug for FoldPlaceholder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FoldPlaceholder")
             .field("constrain_width", &self.constrain_width)
+            .field("text", &self.text)
             .finish()
     }
 }
@@ -63,7 +69,9 @@ impl Eq for FoldPlaceholder {}
 
 impl PartialEq for FoldPlaceholder {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.render, &other.render) && self.constrain_width == other.constrain_width
+        Arc::ptr_eq(&self.render, &other.render)
+            && self.constrain_width == other.constrain_width
+            && self.text == other.text
     }
 }
 
@@ -471,15 +479,20 @@ impl FoldMap {
                         const ELLIPSIS: &str = "⋯";
 
                         let fold_id = fold.id;
+                        let text = fold
+                            .placeholder
+                            .text
+                            .clone()
+                            .unwrap_or_else(|| SharedString::new_static(ELLIPSIS));
                         new_transforms.push(
                             Transform {
                                 summary: TransformSummary {
-                                    output: TextSummary::from(ELLIPSIS),
+                                    output: TextSummary::from(text.as_ref()),
                                     input: inlay_snapshot
                                         .text_summary_for_range(fold_range.start..fold_range.end),
                                 },
                                 placeholder: Some(TransformPlaceholder {
-                                    text: ELLIPSIS,
+                                    text,
                                     renderer: ChunkRenderer {
                                         render: Arc::new(move |cx| {
                                             (fold.placeholder.render)(
@@ -965,7 +978,7 @@ struct Transform {
 
 #[derive(Clone, Debug)]
 struct TransformPlaceholder {
-    text: &'static str,
+    text: SharedString,
     renderer: ChunkRenderer,
 }
 
@@ -1242,7 +1255,7 @@ impl<'a> Iterator for FoldChunks<'a> {
 
             self.output_offset.0 += placeholder.text.len();
             return Some(Chunk {
-                text: placeholder.text,
+                text: &placeholder.text,
                 renderer: Some(placeholder.renderer.clone()),
                 ..Default::default()
             });
@@ -1528,6 +1541,51 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_folds_with_placeholder_text(cx: &mut gpui::AppContext) {
+        init_test(cx);
+        let buffer = MultiBuffer::build_simple("a {\n  b: 1,\n  c: 2\n} d", cx);
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        let mut map = FoldMap::new(inlay_snapshot.clone()).0;
+
+        let (mut writer, _, _) = map.write(inlay_snapshot, vec![]);
+        let (snapshot, edits) = writer.fold(vec![
+            (
+                Point::new(0, 2)..Point::new(3, 1),
+                FoldPlaceholder {
+                    text: Some("{…2 keys…}".into()),
+                    ..FoldPlaceholder::test()
+                },
+            ),
+            (Point::new(3, 2)..Point::new(3, 3), FoldPlaceholder::test()),
+        ]);
+        assert_eq!(snapshot.text(), "a {…2 keys…} ⋯");
+        assert_eq!(
+            edits,
+            &[
+                FoldEdit {
+                    old: FoldOffset(2)..FoldOffset(20),
+                    new: FoldOffset(2)..FoldOffset(16),
+                },
+                FoldEdit {
+                    old: FoldOffset(21)..FoldOffset(22),
+                    new: FoldOffset(17)..FoldOffset(20),
+                },
+            ]
+        );
+
+        // Points within the placeholder text are clipped to either end of it.
+        assert_eq!(
+            snapshot.clip_point(FoldPoint::new(0, 5), Bias::Left),
+            FoldPoint::new(0, 2)
+        );
+        assert_eq!(
+            snapshot.clip_point(FoldPoint::new(0, 5), Bias::Right),
+            FoldPoint::new(0, 16)
+        );
+    }
+
     #[gpui::test]
     fn test_overlapping_folds(cx: &mut gpui::AppContext) {
         let buffer = MultiBuffer::build_simple(&sample_text(5, 6, 'a'), cx);
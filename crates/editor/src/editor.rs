@@ -99,8 +99,8 @@ use itertools::Itertools;
 use language::{
     language_settings::{self, all_language_settings, language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
-    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    CursorShape, Diagnostic, DiagnosticEntry, Documentation, IndentKind, IndentSize, Language,
+    OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{point_to_lsp, BufferRow, CharClassifier, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
@@ -137,6 +137,7 @@ use project::{
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
+pub use selections_collection::MergeBehavior;
 use selections_collection::{
     resolve_selections, MutableSelectionsCollection, SelectionsCollection,
 };
@@ -600,8 +601,16 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_larger_syntax_node_stack: Vec<Box<[Selection<usize>]>>,
+    select_enclosing_scope_stack: Vec<Box<[Selection<usize>]>>,
+    /// The cursor position before the first selection change made inside
+    /// `batch_autoscroll`, and whether any of the changes requested completions.
+    /// The side effects of those changes run once, when the batch ends.
+    batched_selection_change: Option<(Anchor, bool)>,
     ime_transaction: Option<TransactionId>,
+    /// The interval applied to the buffers from the `undo_group_interval` setting, if any.
+    undo_group_interval: Option<Duration>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
+    diagnostic_severity_filter: DiagnosticSeverityFilter,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
 
     project: Option<Model<Project>>,
@@ -619,6 +628,8 @@ pub struct Editor {
     show_line_numbers: Option<bool>,
     use_relative_line_numbers: Option<bool>,
     show_git_diff_gutter: Option<bool>,
+    show_change_markers: bool,
+    unsaved_hunks_cache: RefCell<HashMap<BufferId, UnsavedHunks>>,
     show_code_actions: Option<bool>,
     show_runnables: Option<bool>,
     show_wrap_guides: Option<bool>,
@@ -954,6 +965,23 @@ pub struct ClipboardSelection {
     pub len: usize,
     pub is_entire_line: bool,
     pub first_line_indent: u32,
+    /// Whether this selection was part of a columnar block, i.e. one of several
+    /// single-line selections on consecutive rows.
+    #[serde(default)]
+    pub is_block: bool,
+}
+
+impl ClipboardSelection {
+    /// Returns whether the given selections form a columnar block.
+    pub fn selections_are_block(selections: &[Selection<Point>]) -> bool {
+        selections.len() > 1
+            && selections
+                .iter()
+                .all(|selection| !selection.is_empty() && selection.start.row == selection.end.row)
+            && selections
+                .windows(2)
+                .all(|pair| pair[1].start.row == pair[0].start.row + 1)
+    }
 }
 
 #[derive(Debug)]
@@ -964,6 +992,23 @@ pub(crate) struct NavigationData {
     scroll_top_row: u32,
 }
 
+/// How a row changed since its buffer was last saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    /// Lines were removed just above this row.
+    Deleted,
+}
+
+/// A buffer's line-granular hunks since it was last saved, along with the
+/// versions they were computed for.
+struct UnsavedHunks {
+    version: clock::Global,
+    saved_version: clock::Global,
+    hunks: Arc<[language::Edit<u32>]>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GotoDefinitionKind {
     Symbol,
@@ -1110,39 +1155,7 @@ impl Editor {
     ) -> Self {
         let style = cx.text_style();
         let font_size = style.font_size.to_pixels(cx.rem_size());
-        let editor = cx.view().downgrade();
-        let fold_placeholder = FoldPlaceholder {
-            constrain_width: true,
-            render: Arc::new(move |fold_id, fold_range, cx| {
-                let editor = editor.clone();
-                div()
-                    .id(fold_id)
-                    .bg(cx.theme().colors().ghost_element_background)
-                    .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
-                    .active(|style| style.bg(cx.theme().colors().ghost_element_active))
-                    .rounded_sm()
-                    .size_full()
-                    .cursor_pointer()
-                    .child("⋯")
-                    .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
-                    .on_click(move |_, cx| {
-                        editor
-                            .update(cx, |editor, cx| {
-                                editor.unfold_ranges(
-                                    &[fold_range.start..fold_range.end],
-                                    true,
-                                    false,
-                                    cx,
-                                );
-                                cx.stop_propagation();
-                            })
-                            .ok();
-                    })
-                    .into_any()
-            }),
-            merge_adjacent: true,
-            ..Default::default()
-        };
+        let fold_placeholder = clickable_fold_placeholder(cx.view().downgrade(), None);
         let display_map = cx.new_model(|cx| {
             DisplayMap::new(
                 buffer.clone(),
@@ -1246,8 +1259,12 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_larger_syntax_node_stack: Vec::new(),
+            select_enclosing_scope_stack: Vec::new(),
+            batched_selection_change: None,
+            undo_group_interval: None,
             ime_transaction: Default::default(),
             active_diagnostics: None,
+            diagnostic_severity_filter: DiagnosticSeverityFilter::default(),
             soft_wrap_mode_override,
             completion_provider: project.clone().map(|project| Box::new(project) as _),
             semantics_provider: project.clone().map(|project| Rc::new(project) as _),
@@ -1262,6 +1279,8 @@ impl Editor {
             show_line_numbers: None,
             use_relative_line_numbers: None,
             show_git_diff_gutter: None,
+            show_change_markers: false,
+            unsaved_hunks_cache: Default::default(),
             show_code_actions: None,
             show_runnables: None,
             show_wrap_guides: None,
@@ -1369,6 +1388,7 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        this.apply_undo_group_interval(cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1748,6 +1768,13 @@ impl Editor {
         }
     }
 
+    /// Sets whether overlapping selections are merged when selections change.
+    /// Features that need distinct cursors at the same position can use
+    /// [`MergeBehavior::Keep`].
+    pub fn set_selection_merge_behavior(&mut self, merge_behavior: MergeBehavior) {
+        self.selections.merge_behavior = merge_behavior;
+    }
+
     pub fn set_autoindent(&mut self, autoindent: bool) {
         if autoindent {
             self.autoindent_mode = Some(AutoindentMode::EachLine);
@@ -1919,6 +1946,7 @@ impl Editor {
         self.select_next_state = None;
         self.select_prev_state = None;
         self.select_larger_syntax_node_stack.clear();
+        self.select_enclosing_scope_stack.clear();
         self.invalidate_autoclose_regions(&self.selections.disjoint_anchors(), buffer);
         self.snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
@@ -2039,21 +2067,37 @@ impl Editor {
             if let Some(autoscroll) = autoscroll {
                 self.request_autoscroll(autoscroll, cx);
             }
-            self.selections_did_change(true, &old_cursor_position, request_completions, cx);
-
-            if self.should_open_signature_help_automatically(
-                &old_cursor_position,
-                self.signature_help_state.backspace_pressed(),
-                cx,
-            ) {
-                self.show_signature_help(&ShowSignatureHelp, cx);
+            if self.scroll_manager.autoscroll_batch_depth > 0 {
+                let (_, batched_request_completions) = self
+                    .batched_selection_change
+                    .get_or_insert((old_cursor_position, false));
+                *batched_request_completions |= request_completions;
+            } else {
+                self.local_selections_did_change(&old_cursor_position, request_completions, cx);
             }
-            self.signature_help_state.set_backspace_pressed(false);
         }
 
         result
     }
 
+    fn local_selections_did_change(
+        &mut self,
+        old_cursor_position: &Anchor,
+        request_completions: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.selections_did_change(true, old_cursor_position, request_completions, cx);
+
+        if self.should_open_signature_help_automatically(
+            old_cursor_position,
+            self.signature_help_state.backspace_pressed(),
+            cx,
+        ) {
+            self.show_signature_help(&ShowSignatureHelp, cx);
+        }
+        self.signature_help_state.set_backspace_pressed(false);
+    }
+
     pub fn edit<I, S, T>(&mut self, edits: I, cx: &mut ViewContext<Self>)
     where
         I: IntoIterator<Item = (Range<S>, T)>,
@@ -2435,7 +2479,16 @@ impl Editor {
         let selection_ranges = (start_row.0..=end_row.0)
             .map(DisplayRow)
             .filter_map(|row| {
-                if start_column <= display_map.line_len(row) && !display_map.is_block_line(row) {
+                if display_map.is_block_line(row) {
+                    None
+                } else if start_column > display_map.line_len(row) {
+                    // Rows that end before the rectangle get a cursor at their end.
+                    let end_of_row = DisplayPoint::new(row, display_map.line_len(row));
+                    let cursor = display_map
+                        .clip_point(end_of_row, Bias::Left)
+                        .to_point(display_map);
+                    Some(cursor..cursor)
+                } else {
                     let start = display_map
                         .clip_point(DisplayPoint::new(row, start_column), Bias::Left)
                         .to_point(display_map);
@@ -2447,8 +2500,6 @@ impl Editor {
                     } else {
                         Some(start..end)
                     }
-                } else {
-                    None
                 }
             })
             .collect::<Vec<_>>();
@@ -2459,6 +2510,30 @@ impl Editor {
         cx.notify();
     }
 
+    /// Returns the selections that other replicas have in this editor's
+    /// buffers, as display ranges grouped by replica. Unlike
+    /// [`EditorSnapshot::remote_selections_in_range`], this doesn't require
+    /// the replicas to be known collaborators.
+    pub fn remote_selections(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<(ReplicaId, Vec<Range<DisplayPoint>>)> {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let mut selections_by_replica = BTreeMap::<ReplicaId, Vec<Range<DisplayPoint>>>::default();
+        for (replica_id, _, _, selection) in display_map
+            .buffer_snapshot
+            .selections_in_range(&(Anchor::min()..Anchor::max()), false)
+        {
+            let start = selection.start.to_display_point(&display_map);
+            let end = selection.end.to_display_point(&display_map);
+            selections_by_replica
+                .entry(replica_id)
+                .or_default()
+                .push(start..end);
+        }
+        selections_by_replica.into_iter().collect()
+    }
+
     pub fn has_pending_nonempty_selection(&self) -> bool {
         let pending_nonempty_selection = match self.selections.pending_anchor() {
             Some(Selection { start, end, .. }) => start != end,
@@ -3224,6 +3299,46 @@ impl Editor {
         });
     }
 
+    /// Replaces each selection with the corresponding text, in document order,
+    /// leaving every selection over the text that replaced it. If the number of
+    /// texts doesn't match the number of selections, every selection is
+    /// replaced with all of the texts joined by newlines, as when pasting.
+    pub fn replace_selections_with(&mut self, texts: Vec<String>, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let old_selections = self.selections.all::<usize>(cx);
+        let texts: Vec<Arc<str>> = if texts.len() == old_selections.len() {
+            texts.into_iter().map(Into::into).collect()
+        } else {
+            let text: Arc<str> = texts.join("\n").into();
+            vec![text; old_selections.len()]
+        };
+
+        let mut delta = 0isize;
+        let mut edits = Vec::with_capacity(old_selections.len());
+        let mut new_selections = Vec::with_capacity(old_selections.len());
+        for (selection, text) in old_selections.into_iter().zip(texts) {
+            let start = (selection.start as isize + delta) as usize;
+            new_selections.push(Selection {
+                id: selection.id,
+                start,
+                end: start + text.len(),
+                reversed: false,
+                goal: SelectionGoal::None,
+            });
+            delta += text.len() as isize - selection.range().len() as isize;
+            edits.push((selection.range(), text));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
     pub fn insert(&mut self, text: &str, cx: &mut ViewContext<Self>) {
         let autoindent = text.is_empty().not().then(|| AutoindentMode::Block {
             original_indent_columns: Vec::new(),
@@ -3231,6 +3346,74 @@ impl Editor {
         self.insert_with_autoindent_mode(text, autoindent, cx);
     }
 
+    /// Inserts `text` at the head of every selection, leaving any selected text
+    /// in place. Each selection is collapsed to a cursor after its insertion.
+    pub fn insert_at_cursors(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let text: Arc<str> = text.into();
+        let old_selections = self.selections.all::<usize>(cx);
+        let mut delta = 0;
+        let mut edits = Vec::with_capacity(old_selections.len());
+        let mut new_selections = Vec::with_capacity(old_selections.len());
+        for selection in old_selections {
+            let head = selection.head();
+            delta += text.len();
+            new_selections.push(Selection {
+                id: selection.id,
+                start: head + delta,
+                end: head + delta,
+                reversed: false,
+                goal: SelectionGoal::None,
+            });
+            edits.push((head..head, text.clone()));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
+    /// Moves the text of the newest selection to `target` in a single transaction,
+    /// leaving the moved text selected. Targets inside the selection are ignored.
+    pub fn move_selection_to(&mut self, target: DisplayPoint, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let selection = self.selections.newest::<usize>(cx);
+        let target = target.to_offset(&display_map, Bias::Left);
+        if selection.is_empty() || (selection.start..=selection.end).contains(&target) {
+            return;
+        }
+
+        let text: Arc<str> = display_map
+            .buffer_snapshot
+            .text_for_range(selection.range())
+            .collect::<String>()
+            .into();
+        let removal = (selection.range(), Arc::<str>::from(""));
+        let insertion = (target..target, text.clone());
+        let (new_start, edits) = if target > selection.end {
+            (target - selection.range().len(), [removal, insertion])
+        } else {
+            (target, [insertion, removal])
+        };
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([new_start..new_start + text.len()])
+            });
+        });
+    }
+
     fn insert_with_autoindent_mode(
         &mut self,
         text: &str,
@@ -5867,6 +6050,82 @@ impl Editor {
         });
     }
 
+    pub fn copy_indent_from_above_line(
+        &mut self,
+        _: &CopyIndentFromAboveLine,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let mut selections = self.selections.all::<Point>(cx);
+        let snapshot = &display_map.buffer_snapshot;
+
+        let indent_text = |row: MultiBufferRow| {
+            let indent_len = snapshot.indent_size_for_line(row).len;
+            snapshot
+                .text_for_range(Point::new(row.0, 0)..Point::new(row.0, indent_len))
+                .collect::<String>()
+        };
+
+        // Maps each re-indented row to its old indentation length and its new indentation.
+        // Rows are visited in ascending order, so a row directly below a re-indented row
+        // copies the new indentation rather than the old one.
+        let mut reindented_rows = HashMap::<u32, (u32, String)>::default();
+        let mut edits = Vec::new();
+        for selection in &selections {
+            for row in selection.spanned_rows(false, &display_map).iter_rows() {
+                if row.0 == 0 || reindented_rows.contains_key(&row.0) {
+                    continue;
+                }
+                let above_indent = reindented_rows
+                    .get(&(row.0 - 1))
+                    .map(|(_, new_indent)| new_indent.clone())
+                    .unwrap_or_else(|| indent_text(row.previous_row()));
+                let current_indent = indent_text(row);
+
+                // Compare the indentation as it's displayed, so that a tab is as wide as
+                // the spaces it stands for.
+                let tab_size = snapshot.settings_at(Point::new(row.0, 0), cx).tab_size;
+                if char_len_with_expanded_tabs(0, &above_indent, tab_size)
+                    <= char_len_with_expanded_tabs(0, &current_indent, tab_size)
+                {
+                    continue;
+                }
+                let current_indent_len = current_indent.len() as u32;
+                edits.push((
+                    Point::new(row.0, 0)..Point::new(row.0, current_indent_len),
+                    above_indent.clone(),
+                ));
+                reindented_rows.insert(row.0, (current_indent_len, above_indent));
+            }
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+
+        for selection in &mut selections {
+            for point in [&mut selection.start, &mut selection.end] {
+                if let Some((old_len, new_indent)) = reindented_rows.get(&point.row) {
+                    let new_len = new_indent.len() as u32;
+                    if point.column <= *old_len {
+                        point.column = new_len;
+                    } else {
+                        point.column = point.column - old_len + new_len;
+                    }
+                }
+            }
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+        });
+    }
+
     pub fn delete_line(&mut self, _: &DeleteLine, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let selections = self.selections.all::<Point>(cx);
@@ -6019,7 +6278,7 @@ impl Editor {
         _: &SortLinesCaseSensitive,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_lines(cx, |lines| lines.sort())
+        self.manipulate_lines("Sort Lines", cx, |lines| lines.sort())
     }
 
     pub fn sort_lines_case_insensitive(
@@ -6027,7 +6286,29 @@ impl Editor {
         _: &SortLinesCaseInsensitive,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_lines(cx, |lines| lines.sort_by_key(|line| line.to_lowercase()))
+        self.manipulate_lines("Sort Lines", cx, |lines| {
+            lines.sort_by_key(|line| line.to_lowercase())
+        })
+    }
+
+    /// Sorts lines by the value of the first number appearing in each of them,
+    /// so that `item2` comes before `item10`.
+    pub fn sort_lines_numeric(&mut self, action: &SortLinesNumeric, cx: &mut ViewContext<Self>) {
+        let numberless_lines_last = action.numberless_lines_last;
+        self.manipulate_lines("Sort Lines", cx, |lines| {
+            lines.sort_by(|a, b| {
+                let a = first_number_in_line(a);
+                let b = first_number_in_line(b);
+                match (a, b) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) if numberless_lines_last => Ordering::Less,
+                    (Some(_), None) => Ordering::Greater,
+                    (None, Some(_)) if numberless_lines_last => Ordering::Greater,
+                    (None, Some(_)) => Ordering::Less,
+                    (None, None) => Ordering::Equal,
+                }
+            })
+        })
     }
 
     pub fn unique_lines_case_insensitive(
@@ -6035,7 +6316,7 @@ impl Editor {
         _: &UniqueLinesCaseInsensitive,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_lines(cx, |lines| {
+        self.manipulate_lines("Unique Lines", cx, |lines| {
             let mut seen = HashSet::default();
             lines.retain(|line| seen.insert(line.to_lowercase()));
         })
@@ -6046,7 +6327,7 @@ impl Editor {
         _: &UniqueLinesCaseSensitive,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_lines(cx, |lines| {
+        self.manipulate_lines("Unique Lines", cx, |lines| {
             let mut seen = HashSet::default();
             lines.retain(|line| seen.insert(*line));
         })
@@ -6159,15 +6440,21 @@ impl Editor {
     }
 
     pub fn reverse_lines(&mut self, _: &ReverseLines, cx: &mut ViewContext<Self>) {
-        self.manipulate_lines(cx, |lines| lines.reverse())
+        self.manipulate_lines("Reverse Lines", cx, |lines| lines.reverse())
     }
 
     pub fn shuffle_lines(&mut self, _: &ShuffleLines, cx: &mut ViewContext<Self>) {
-        self.manipulate_lines(cx, |lines| lines.shuffle(&mut thread_rng()))
+        self.manipulate_lines("Shuffle Lines", cx, |lines| {
+            lines.shuffle(&mut thread_rng())
+        })
     }
 
-    fn manipulate_lines<Fn>(&mut self, cx: &mut ViewContext<Self>, mut callback: Fn)
-    where
+    fn manipulate_lines<Fn>(
+        &mut self,
+        description: &'static str,
+        cx: &mut ViewContext<Self>,
+        mut callback: Fn,
+    ) where
         Fn: FnMut(&mut Vec<&str>),
     {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -6226,7 +6513,7 @@ impl Editor {
             }
         }
 
-        self.transact(cx, |this, cx| {
+        self.transact_with_description(description, cx, |this, cx| {
             let buffer = this.buffer.update(cx, |buffer, cx| {
                 buffer.edit(edits, None, cx);
                 buffer.snapshot(cx)
@@ -6305,17 +6592,56 @@ impl Editor {
         _: &ConvertToOppositeCase,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_text(cx, |text| {
-            text.chars()
-                .fold(String::with_capacity(text.len()), |mut t, c| {
-                    if c.is_uppercase() {
-                        t.extend(c.to_lowercase());
-                    } else {
-                        t.extend(c.to_uppercase());
-                    }
-                    t
-                })
-        })
+        self.manipulate_text(cx, invert_case)
+    }
+
+    /// Flips the case of every character in each selection. An empty selection
+    /// flips the character after the cursor and moves past it.
+    pub fn toggle_case(&mut self, _: &ToggleCase, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut delta = 0isize;
+        for selection in self.selections.all::<usize>(cx) {
+            let range = if selection.is_empty() {
+                match buffer.chars_at(selection.start).next() {
+                    Some(c) if c != '\n' => selection.start..selection.start + c.len_utf8(),
+                    _ => selection.start..selection.start,
+                }
+            } else {
+                selection.range()
+            };
+            let text = invert_case(&buffer.text_for_range(range.clone()).collect::<String>());
+
+            let start = (range.start as isize + delta) as usize;
+            let end = start + text.len();
+            new_selections.push(if selection.is_empty() {
+                Selection {
+                    start: end,
+                    end,
+                    reversed: false,
+                    goal: SelectionGoal::None,
+                    ..selection
+                }
+            } else {
+                Selection {
+                    start,
+                    end,
+                    goal: SelectionGoal::None,
+                    ..selection
+                }
+            });
+            delta += text.len() as isize - range.len() as isize;
+            if !range.is_empty() {
+                edits.push((range, text));
+            }
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
     }
 
     fn manipulate_text<Fn>(&mut self, cx: &mut ViewContext<Self>, mut callback: Fn)
@@ -6887,6 +7213,8 @@ impl Editor {
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selections = self.selections.all::<Point>(cx);
         let mut clipboard_selections = Vec::with_capacity(selections.len());
+        let is_block =
+            !self.selections.line_mode && ClipboardSelection::selections_are_block(&selections);
         {
             let max_point = buffer.max_point();
             let mut is_first = true;
@@ -6917,6 +7245,7 @@ impl Editor {
                     first_line_indent: buffer
                         .indent_size_for_line(MultiBufferRow(selection.start.row))
                         .len,
+                    is_block,
                 });
             }
         }
@@ -6966,6 +7295,8 @@ impl Editor {
         let mut text = String::new();
 
         let mut clipboard_selections = Vec::with_capacity(selections.len());
+        let is_block =
+            !self.selections.line_mode && ClipboardSelection::selections_are_block(&selections);
         {
             let max_point = buffer.max_point();
             let mut is_first = true;
@@ -6991,6 +7322,7 @@ impl Editor {
                     len,
                     is_entire_line,
                     first_line_indent: buffer.indent_size_for_line(MultiBufferRow(start.row)).len,
+                    is_block,
                 });
             }
         }
@@ -7017,6 +7349,14 @@ impl Editor {
         self.transact(cx, |this, cx| {
             if let Some(mut clipboard_selections) = clipboard_selections {
                 let old_selections = this.selections.all::<usize>(cx);
+                if old_selections.len() == 1
+                    && clipboard_selections.len() > 1
+                    && clipboard_selections.iter().all(|s| s.is_block)
+                {
+                    this.paste_block(&clipboard_text, &clipboard_selections, cx);
+                    return;
+                }
+
                 let all_selections_were_entire_line =
                     clipboard_selections.iter().all(|s| s.is_entire_line);
                 let first_selection_indent_column =
@@ -7089,6 +7429,88 @@ impl Editor {
         });
     }
 
+    /// Pastes a columnar block into a single selection, placing each slice of the clipboard
+    /// text on its own row, starting at the selection's column. The selected text is removed
+    /// first. Columns are measured in characters with tabs expanded, rows that are too short
+    /// are padded with spaces, and rows are appended when the block extends past the last line.
+    fn paste_block(
+        &mut self,
+        clipboard_text: &str,
+        clipboard_selections: &[ClipboardSelection],
+        cx: &mut ViewContext<Self>,
+    ) {
+        let selection = self.selections.newest::<Point>(cx);
+        if !selection.is_empty() {
+            self.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(selection.range(), "")], None, cx)
+            });
+            self.change_selections(None, cx, |s| {
+                s.select_ranges([selection.start..selection.start])
+            });
+        }
+
+        self.buffer.update(cx, |buffer, cx| {
+            let snapshot = buffer.read(cx);
+            let max_point = snapshot.max_point();
+            let start = selection.start;
+            let tab_size = snapshot.settings_at(start, cx).tab_size;
+            let line_prefix = snapshot
+                .text_for_range(Point::new(start.row, 0)..start)
+                .collect::<String>();
+            let column = char_len_with_expanded_tabs(0, &line_prefix, tab_size);
+
+            let mut start_offset = 0;
+            let mut edits = Vec::new();
+            let mut appended_rows = String::new();
+            for (ix, clipboard_selection) in clipboard_selections.iter().enumerate() {
+                let end_offset = start_offset + clipboard_selection.len;
+                let slice = &clipboard_text[start_offset..end_offset];
+                start_offset = end_offset + 1;
+
+                if ix == 0 {
+                    edits.push((start..start, slice.to_string()));
+                    continue;
+                }
+
+                let row = start.row + ix as u32;
+                if row > max_point.row {
+                    appended_rows.push('\n');
+                    appended_rows.push_str(&" ".repeat(column));
+                    appended_rows.push_str(slice);
+                    continue;
+                }
+
+                // Find the first character boundary at or past the block's column.
+                let mut width = 0;
+                let mut byte_column = 0;
+                for ch in snapshot.chars_at(Point::new(row, 0)) {
+                    if ch == '\n' || width >= column {
+                        break;
+                    }
+                    width += if ch == '\t' {
+                        tab_size.get() as usize - width % tab_size.get() as usize
+                    } else {
+                        1
+                    };
+                    byte_column += ch.len_utf8() as u32;
+                }
+
+                let position = Point::new(row, byte_column);
+                let padding = " ".repeat(column.saturating_sub(width));
+                edits.push((position..position, padding + slice));
+            }
+            if !appended_rows.is_empty() {
+                edits.push((max_point..max_point, appended_rows));
+            }
+            drop(snapshot);
+
+            buffer.edit(edits, None, cx);
+        });
+
+        let selections = self.selections.all::<usize>(cx);
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+    }
+
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
         if let Some(item) = cx.read_from_clipboard() {
             let entries = item.entries();
@@ -7114,8 +7536,13 @@ impl Editor {
         }
 
         if let Some(transaction_id) = self.buffer.update(cx, |buffer, cx| buffer.undo(cx)) {
-            if let Some((selections, _)) =
-                self.selection_history.transaction(transaction_id).cloned()
+            // Restore every cursor this editor had before the transaction. If none were
+            // recorded, keep the current selections rather than collapsing them.
+            if let Some((selections, _)) = self
+                .selection_history
+                .transaction(transaction_id)
+                .filter(|(selections, _)| !selections.is_empty())
+                .cloned()
             {
                 self.change_selections(None, cx, |s| {
                     s.select_anchors(selections.to_vec());
@@ -7129,14 +7556,51 @@ impl Editor {
         }
     }
 
+    /// Undoes every transaction made after `checkpoint`, e.g. to revert all
+    /// changes since the buffer was saved. The cursors are restored to where
+    /// they were before the earliest undone transaction.
+    pub fn undo_to_checkpoint(&mut self, checkpoint: TransactionId, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let undone = self
+            .buffer
+            .update(cx, |buffer, cx| buffer.undo_to_checkpoint(checkpoint, cx));
+        let Some(earliest_transaction_id) = undone.last().copied() else {
+            return;
+        };
+
+        if let Some((selections, _)) = self
+            .selection_history
+            .transaction(earliest_transaction_id)
+            .filter(|(selections, _)| !selections.is_empty())
+            .cloned()
+        {
+            self.change_selections(None, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(cx);
+        self.refresh_inline_completion(true, false, cx);
+        for transaction_id in undone {
+            cx.emit(EditorEvent::Edited { transaction_id });
+            cx.emit(EditorEvent::TransactionUndone { transaction_id });
+        }
+    }
+
     pub fn redo(&mut self, _: &Redo, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
             return;
         }
 
         if let Some(transaction_id) = self.buffer.update(cx, |buffer, cx| buffer.redo(cx)) {
-            if let Some((_, Some(selections))) =
-                self.selection_history.transaction(transaction_id).cloned()
+            if let Some((_, Some(selections))) = self
+                .selection_history
+                .transaction(transaction_id)
+                .filter(|(_, selections)| selections.as_ref().map_or(false, |s| !s.is_empty()))
+                .cloned()
             {
                 self.change_selections(None, cx, |s| {
                     s.select_anchors(selections.to_vec());
@@ -7977,8 +8441,39 @@ impl Editor {
         });
     }
 
-    pub fn select_line(&mut self, _: &SelectLine, cx: &mut ViewContext<Self>) {
-        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+    /// Selects every part of the buffer that isn't currently selected. Empty
+    /// selections split the buffer at their position.
+    pub fn invert_selections(&mut self, _: &InvertSelections, cx: &mut ViewContext<Self>) {
+        let len = self.buffer.read(cx).read(cx).len();
+        let selections = self.selections.all::<usize>(cx);
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for selection in &selections {
+            if selection.start > start {
+                ranges.push(start..selection.start);
+            }
+            start = selection.end;
+        }
+        if start < len {
+            ranges.push(start..len);
+        }
+
+        // Everything was selected, so there is nothing left to select.
+        if ranges.is_empty() {
+            let head = self.selections.newest::<usize>(cx).head();
+            ranges.push(head..head);
+        }
+
+        // The ranges on either side of a cursor touch, so keep them from being merged.
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            let merge_behavior = mem::replace(&mut s.merge_behavior, MergeBehavior::Keep);
+            s.select_ranges(ranges);
+            s.merge_behavior = merge_behavior;
+        });
+    }
+
+    pub fn select_line(&mut self, _: &SelectLine, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let mut selections = self.selections.all::<Point>(cx);
         let max_point = display_map.buffer_snapshot.max_point();
         for selection in &mut selections {
@@ -7992,6 +8487,20 @@ impl Editor {
         });
     }
 
+    pub fn expand_to_line(&mut self, _: &ExpandToLine, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let mut selections = self.selections.all::<Point>(cx);
+        for selection in &mut selections {
+            let rows = selection.spanned_rows(false, &display_map);
+            let last_row = MultiBufferRow(rows.end.0 - 1);
+            selection.start = Point::new(rows.start.0, 0);
+            selection.end = Point::new(last_row.0, display_map.buffer_snapshot.line_len(last_row));
+        }
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select(selections);
+        });
+    }
+
     pub fn split_selection_into_lines(
         &mut self,
         _: &SplitSelectionIntoLines,
@@ -8017,6 +8526,105 @@ impl Editor {
         });
     }
 
+    /// Wraps each non-empty selection in the given pair, keeping the selection
+    /// over the original text. Empty selections are left untouched.
+    pub fn surround_selections(&mut self, action: &SurroundSelections, cx: &mut ViewContext<Self>) {
+        let start: Arc<str> = action.start.as_str().into();
+        let end: Arc<str> = action.end.as_str().into();
+        let mut selections = self.selections.all::<usize>(cx);
+        let mut edits = Vec::new();
+        let mut delta = 0;
+        for selection in &mut selections {
+            if !selection.is_empty() {
+                edits.push((selection.start..selection.start, start.clone()));
+                edits.push((selection.end..selection.end, end.clone()));
+            }
+            selection.start += delta;
+            selection.end += delta;
+            if !selection.is_empty() {
+                selection.start += start.len();
+                selection.end += start.len();
+                delta += start.len() + end.len();
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+        });
+    }
+
+    /// Inserts spaces before the first cursor on each line so that all of them
+    /// end up in the same displayed column, optionally moving each cursor to the
+    /// next occurrence of a character on its line first. Tabs before a cursor
+    /// count as the columns they're displayed in.
+    pub fn align_cursors(&mut self, action: &AlignCursors, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut selections = self.selections.all::<Point>(cx);
+
+        let mut targets: Vec<(usize, Point, usize)> = Vec::new();
+        for (ix, selection) in selections.iter().enumerate() {
+            let head = selection.head();
+            if targets
+                .last()
+                .map_or(false, |(_, position, _)| position.row == head.row)
+            {
+                continue;
+            }
+
+            let position = if let Some(character) = action.character {
+                let line_end = Point::new(head.row, buffer.line_len(MultiBufferRow(head.row)));
+                let rest_of_line = buffer.text_for_range(head..line_end).collect::<String>();
+                let Some(offset) = rest_of_line.find(character) else {
+                    continue;
+                };
+                Point::new(head.row, head.column + offset as u32)
+            } else {
+                head
+            };
+            let line_prefix = buffer
+                .text_for_range(Point::new(position.row, 0)..position)
+                .collect::<String>();
+            let tab_size = buffer.settings_at(position, cx).tab_size;
+            let column = char_len_with_expanded_tabs(0, &line_prefix, tab_size);
+            targets.push((ix, position, column));
+        }
+
+        let Some(max_column) = targets.iter().map(|(_, _, column)| *column).max() else {
+            return;
+        };
+
+        let mut edits = Vec::new();
+        for (ix, position, column) in &targets {
+            let padding = max_column - column;
+            if padding > 0 {
+                edits.push((*position..*position, " ".repeat(padding)));
+            }
+
+            for selection in &mut selections {
+                for point in [&mut selection.start, &mut selection.end] {
+                    if point.row == position.row && point.column >= position.column {
+                        point.column += padding as u32;
+                    }
+                }
+            }
+            if action.character.is_some() {
+                let aligned = Point::new(position.row, position.column + padding as u32);
+                selections[*ix].collapse_to(aligned, SelectionGoal::None);
+            }
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+        });
+    }
+
     pub fn add_selection_above(&mut self, _: &AddSelectionAbove, cx: &mut ViewContext<Self>) {
         self.add_selection(true, cx);
     }
@@ -8025,10 +8633,46 @@ impl Editor {
         self.add_selection(false, cx);
     }
 
+    /// Returns whether another cursor can be added to the given number of selections
+    /// without exceeding the `max_cursors` setting, reporting the limit if it can't.
+    fn can_add_cursors(&self, selection_count: usize, cx: &mut ViewContext<Self>) -> bool {
+        let max_cursors = EditorSettings::get_global(cx).max_cursors;
+        if selection_count < max_cursors {
+            true
+        } else {
+            Self::report_max_cursors_reached(max_cursors, cx);
+            false
+        }
+    }
+
+    fn report_max_cursors_reached(max_cursors: usize, cx: &mut ViewContext<Self>) {
+        cx.emit(EditorEvent::MaxCursorsReached { max_cursors });
+        cx.window_context().defer(move |cx| {
+            struct MaxCursorsReached;
+            let Some(workspace) = cx.window_handle().downcast::<Workspace>() else {
+                return;
+            };
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<MaxCursorsReached>(),
+                            format!("Reached the limit of {max_cursors} cursors"),
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                })
+                .ok();
+        });
+    }
+
     fn add_selection(&mut self, above: bool, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let mut selections = self.selections.all::<Point>(cx);
         let text_layout_details = self.text_layout_details(cx);
+        let max_cursors = EditorSettings::get_global(cx).max_cursors;
+        let mut max_cursors_reached = false;
         let mut state = self.add_selections_state.take().unwrap_or_else(|| {
             let oldest_selection = selections.iter().min_by_key(|s| s.id).unwrap().clone();
             let range = oldest_selection.display_range(&display_map).sorted();
@@ -8040,6 +8684,11 @@ impl Editor {
             selections.clear();
             let mut stack = Vec::new();
             for row in range.start.row().0..=range.end.row().0 {
+                if !stack.is_empty() && stack.len() >= max_cursors {
+                    max_cursors_reached = true;
+                    break;
+                }
+
                 if let Some(selection) = self.selections.build_columnar_selection(
                     &display_map,
                     DisplayRow(row),
@@ -8061,7 +8710,10 @@ impl Editor {
 
         let last_added_selection = *state.stack.last().unwrap();
         let mut new_selections = Vec::new();
-        if above == state.above {
+        if above == state.above && (max_cursors_reached || selections.len() >= max_cursors) {
+            max_cursors_reached = true;
+            new_selections = selections;
+        } else if above == state.above {
             let end_row = if above {
                 DisplayRow(0)
             } else {
@@ -8126,6 +8778,9 @@ impl Editor {
         if state.stack.len() > 1 {
             self.add_selections_state = Some(state);
         }
+        if max_cursors_reached {
+            Self::report_max_cursors_reached(max_cursors, cx);
+        }
     }
 
     pub fn select_next_match_internal(
@@ -8195,13 +8850,15 @@ impl Editor {
                 }
 
                 if let Some(next_selected_range) = next_selected_range {
-                    select_next_match_ranges(
-                        self,
-                        next_selected_range,
-                        replace_newest,
-                        autoscroll,
-                        cx,
-                    );
+                    if replace_newest || self.can_add_cursors(selections.len(), cx) {
+                        select_next_match_ranges(
+                            self,
+                            next_selected_range,
+                            replace_newest,
+                            autoscroll,
+                            cx,
+                        );
+                    }
                 } else {
                     select_next_state.done = true;
                 }
@@ -8306,7 +8963,12 @@ impl Editor {
             return Ok(());
         }
 
-        let mut new_selections = self.selections.all::<usize>(cx);
+        let existing_selections = self.selections.all::<usize>(cx);
+        let max_cursors = EditorSettings::get_global(cx).max_cursors;
+        let max_new_selections = max_cursors.saturating_sub(existing_selections.len());
+        let mut max_cursors_reached = false;
+        let mut new_selections = Vec::new();
+        let mut existing_ix = 0;
 
         let buffer = &display_map.buffer_snapshot;
         let query_matches = select_next_state
@@ -8323,6 +8985,26 @@ impl Editor {
                 || (!movement::is_inside_word(&display_map, display_range.start)
                     && !movement::is_inside_word(&display_map, display_range.end))
             {
+                // Matches overlapping an existing selection would be merged into it, so they
+                // don't count towards the limit.
+                while existing_selections
+                    .get(existing_ix)
+                    .map_or(false, |selection| selection.end <= offset_range.start)
+                {
+                    existing_ix += 1;
+                }
+                if existing_selections
+                    .get(existing_ix)
+                    .map_or(false, |selection| selection.range().overlaps(&offset_range))
+                {
+                    continue;
+                }
+
+                if new_selections.len() >= max_new_selections {
+                    max_cursors_reached = true;
+                    break;
+                }
+
                 self.selections.change_with(cx, |selections| {
                     new_selections.push(Selection {
                         id: selections.new_selection_id(),
@@ -8335,6 +9017,7 @@ impl Editor {
             }
         }
 
+        new_selections.extend(existing_selections);
         new_selections.sort_by_key(|selection| selection.start);
         let mut ix = 0;
         while ix + 1 < new_selections.len() {
@@ -8364,6 +9047,9 @@ impl Editor {
         self.change_selections(Some(Autoscroll::fit()), cx, |selections| {
             selections.select(new_selections)
         });
+        if max_cursors_reached {
+            Self::report_max_cursors_reached(max_cursors, cx);
+        }
 
         Ok(())
     }
@@ -8425,13 +9111,15 @@ impl Editor {
                 }
 
                 if let Some(next_selected_range) = next_selected_range {
-                    self.unfold_ranges(&[next_selected_range.clone()], false, true, cx);
-                    self.change_selections(Some(Autoscroll::newest()), cx, |s| {
-                        if action.replace_newest {
-                            s.delete(s.newest_anchor().id);
-                        }
-                        s.insert_range(next_selected_range);
-                    });
+                    if action.replace_newest || self.can_add_cursors(selections.len(), cx) {
+                        self.unfold_ranges(&[next_selected_range.clone()], false, true, cx);
+                        self.change_selections(Some(Autoscroll::newest()), cx, |s| {
+                            if action.replace_newest {
+                                s.delete(s.newest_anchor().id);
+                            }
+                            s.insert_range(next_selected_range);
+                        });
+                    }
                 } else {
                     select_prev_state.done = true;
                 }
@@ -8942,6 +9630,71 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    /// Grows each selection to the contents of its innermost enclosing bracket pair or, if it
+    /// already covers those contents, to include the brackets themselves. Buffers without a
+    /// grammar fall back to matching brackets in the text.
+    pub fn select_enclosing_scope(&mut self, _: &SelectEnclosingScope, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+
+        let mut stack = mem::take(&mut self.select_enclosing_scope_stack);
+        let mut selected_larger_scope = false;
+        let new_selections = old_selections
+            .iter()
+            .map(|selection| {
+                let old_range = selection.range();
+                let has_grammar = buffer
+                    .language_at(old_range.start)
+                    .map_or(false, |language| language.grammar().is_some());
+                let bracket_ranges = if has_grammar {
+                    buffer
+                        .enclosing_bracket_ranges(old_range.clone())
+                        .map(|ranges| ranges.collect::<Vec<_>>())
+                } else {
+                    buffer.text_enclosing_bracket_ranges(old_range.clone())
+                };
+                let new_range = bracket_ranges
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|(open, close)| [open.end..close.start, open.start..close.end])
+                    .filter(|range| {
+                        range.start <= old_range.start
+                            && range.end >= old_range.end
+                            && range.len() > old_range.len()
+                    })
+                    .min_by_key(|range| range.len())
+                    .unwrap_or_else(|| old_range.clone());
+
+                selected_larger_scope |= new_range != old_range;
+                Selection {
+                    id: selection.id,
+                    start: new_range.start,
+                    end: new_range.end,
+                    goal: SelectionGoal::None,
+                    reversed: selection.reversed,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if selected_larger_scope {
+            stack.push(old_selections);
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        }
+        self.select_enclosing_scope_stack = stack;
+    }
+
+    pub fn shrink_enclosing_scope(&mut self, _: &ShrinkEnclosingScope, cx: &mut ViewContext<Self>) {
+        let mut stack = mem::take(&mut self.select_enclosing_scope_stack);
+        if let Some(selections) = stack.pop() {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(selections.to_vec());
+            });
+        }
+        self.select_enclosing_scope_stack = stack;
+    }
+
     fn refresh_runnables(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
         if !EditorSettings::get_global(cx).gutter.runnables {
             self.clear_tasks();
@@ -9151,6 +9904,83 @@ impl Editor {
         });
     }
 
+    /// Extends each selection's head to the far side of the innermost bracket pair
+    /// that encloses it, keeping the tail in place. Invoking it again grows the
+    /// selection to the next enclosing pair.
+    pub fn select_to_enclosing_bracket(
+        &mut self,
+        _: &SelectToEnclosingBracket,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.move_offsets_with(|snapshot, selection| {
+                let head = selection.head();
+                let reversed = selection.reversed;
+                let Some((open, close)) = snapshot.innermost_enclosing_bracket_ranges(
+                    selection.start..selection.end,
+                    Some(&|open: Range<usize>, close: Range<usize>| {
+                        if reversed {
+                            open.start < head
+                        } else {
+                            close.end > head
+                        }
+                    }),
+                ) else {
+                    return;
+                };
+
+                let destination = if reversed { open.start } else { close.end };
+                selection.set_head(destination, SelectionGoal::None);
+            })
+        });
+    }
+
+    /// Expands each selection to the contents of the innermost pair of the given
+    /// delimiters enclosing it. Bracket pairs come from the language's bracket
+    /// query, while identical delimiters such as quotes come from the enclosing
+    /// string node, or from the unescaped quotes on the selection's line.
+    pub fn select_inside_delimiters(
+        &mut self,
+        action: &SelectInsideDelimiters,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let SelectInsideDelimiters {
+            open,
+            close,
+            include_delimiters,
+        } = *action;
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.move_offsets_with(|snapshot, selection| {
+                let range = selection.start..selection.end;
+                let delimiters = if open == close {
+                    enclosing_quote_ranges(snapshot, range.clone(), open)
+                } else {
+                    snapshot.innermost_enclosing_bracket_ranges(
+                        range.clone(),
+                        Some(&|open_range: Range<usize>, close_range: Range<usize>| {
+                            open_range.end <= range.start
+                                && close_range.start >= range.end
+                                && snapshot.chars_at(open_range.start).next() == Some(open)
+                                && snapshot.chars_at(close_range.start).next() == Some(close)
+                        }),
+                    )
+                };
+
+                if let Some((open_range, close_range)) = delimiters {
+                    let new_range = if include_delimiters {
+                        open_range.start..close_range.end
+                    } else {
+                        open_range.end..close_range.start
+                    };
+                    selection.start = new_range.start;
+                    selection.end = new_range.end;
+                    selection.reversed = false;
+                    selection.goal = SelectionGoal::None;
+                }
+            });
+        });
+    }
+
     pub fn undo_selection(&mut self, _: &UndoSelection, cx: &mut ViewContext<Self>) {
         self.end_selection(cx);
         self.selection_history.mode = SelectionHistoryMode::Undoing;
@@ -9235,31 +10065,58 @@ impl Editor {
         })
     }
 
-    fn go_to_diagnostic(&mut self, _: &GoToDiagnostic, cx: &mut ViewContext<Self>) {
-        self.go_to_diagnostic_impl(Direction::Next, cx)
+    /// Returns the severity and message of the most severe diagnostic at the
+    /// given point, preferring the most specific range among equally severe ones.
+    pub fn diagnostic_message_at(
+        &mut self,
+        point: DisplayPoint,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<(DiagnosticSeverity, String)> {
+        let snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let offset = point.to_offset(&snapshot, Bias::Left);
+        let buffer = &snapshot.buffer_snapshot;
+        buffer
+            .diagnostics_in_range(offset..offset, false)
+            .min_by_key(|entry| {
+                let range = entry.range.to_offset(buffer);
+                (entry.diagnostic.severity, range.len())
+            })
+            .map(|entry| (entry.diagnostic.severity, entry.diagnostic.message))
+    }
+
+    fn go_to_diagnostic(&mut self, action: &GoToDiagnostic, cx: &mut ViewContext<Self>) {
+        let severity = action.severity.unwrap_or(self.diagnostic_severity_filter);
+        self.go_to_diagnostic_with_severity(Direction::Next, severity, cx)
     }
 
-    fn go_to_prev_diagnostic(&mut self, _: &GoToPrevDiagnostic, cx: &mut ViewContext<Self>) {
-        self.go_to_diagnostic_impl(Direction::Prev, cx)
+    fn go_to_prev_diagnostic(&mut self, action: &GoToPrevDiagnostic, cx: &mut ViewContext<Self>) {
+        let severity = action.severity.unwrap_or(self.diagnostic_severity_filter);
+        self.go_to_diagnostic_with_severity(Direction::Prev, severity, cx)
+    }
+
+    /// Sets which diagnostics are visited by diagnostic navigation when the
+    /// action doesn't specify a severity.
+    pub fn set_diagnostic_severity_filter(&mut self, filter: DiagnosticSeverityFilter) {
+        self.diagnostic_severity_filter = filter;
     }
 
     pub fn go_to_diagnostic_impl(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        self.go_to_diagnostic_with_severity(direction, self.diagnostic_severity_filter, cx)
+    }
+
+    fn go_to_diagnostic_with_severity(
+        &mut self,
+        direction: Direction,
+        severity: DiagnosticSeverityFilter,
+        cx: &mut ViewContext<Self>,
+    ) {
         let buffer = self.buffer.read(cx).snapshot(cx);
         let selection = self.selections.newest::<usize>(cx);
 
         // If there is an active Diagnostic Popover jump to its diagnostic instead.
         if direction == Direction::Next {
             if let Some(popover) = self.hover_state.diagnostic_popover.as_ref() {
-                self.activate_diagnostics(popover.group_id(), cx);
-                if let Some(active_diagnostics) = self.active_diagnostics.as_ref() {
-                    let primary_range_start = active_diagnostics.primary_range.start;
-                    self.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                        let mut new_selection = s.newest_anchor().clone();
-                        new_selection.collapse_to(primary_range_start, SelectionGoal::None);
-                        s.select_anchors(vec![new_selection.clone()]);
-                    });
-                    self.refresh_inline_completion(false, true, cx);
-                }
+                self.go_to_diagnostic_group(popover.group_id(), cx);
                 return;
             }
         }
@@ -9309,7 +10166,7 @@ impl Editor {
                 })
                 .find_map(|entry| {
                     if entry.diagnostic.is_primary
-                        && entry.diagnostic.severity <= DiagnosticSeverity::WARNING
+                        && severity.includes(entry.diagnostic.severity)
                         && !(entry.range.start == entry.range.end)
                         // if we match with the active diagnostic, skip it
                         && Some(entry.diagnostic.group_id)
@@ -9356,6 +10213,74 @@ impl Editor {
         }
     }
 
+    /// Returns all diagnostics in the editor's buffer, ordered by position, e.g.
+    /// for listing them in a panel.
+    pub fn diagnostics(&self, cx: &AppContext) -> Vec<DiagnosticEntry<Anchor>> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        buffer
+            .diagnostics_in_range(0..buffer.len(), false)
+            .collect()
+    }
+
+    /// Counts the primary diagnostics in the editor's buffer by severity, e.g.
+    /// for showing "3 errors, 5 warnings" in a status bar.
+    pub fn diagnostic_count_by_severity(
+        &self,
+        cx: &AppContext,
+    ) -> BTreeMap<DiagnosticSeverity, usize> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut counts = BTreeMap::default();
+        for entry in buffer.diagnostics_in_range(0..buffer.len(), false) {
+            if entry.diagnostic.is_primary {
+                *counts.entry(entry.diagnostic.severity).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// Shows the messages of the diagnostic group under the newest cursor
+    /// without moving it, or hides them if that group is already shown.
+    pub fn toggle_diagnostic_at_cursor(
+        &mut self,
+        _: &ToggleDiagnosticAtCursor,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest::<usize>(cx).head();
+        let group_id = buffer
+            .diagnostics_in_range(head..head, false)
+            .min_by_key(|entry| {
+                (
+                    entry.diagnostic.severity,
+                    entry.range.to_offset(&buffer).len(),
+                )
+            })
+            .map(|entry| entry.diagnostic.group_id);
+
+        let active_group_id = self.active_diagnostics.as_ref().map(|d| d.group_id);
+        match group_id {
+            Some(group_id) if active_group_id != Some(group_id) => {
+                self.activate_diagnostics(group_id, cx);
+            }
+            _ => self.dismiss_diagnostics(cx),
+        }
+    }
+
+    /// Moves the cursor to the primary diagnostic of the given group and shows
+    /// the group's messages.
+    pub fn go_to_diagnostic_group(&mut self, group_id: usize, cx: &mut ViewContext<Self>) {
+        self.activate_diagnostics(group_id, cx);
+        if let Some(active_diagnostics) = self.active_diagnostics.as_ref() {
+            let primary_range_start = active_diagnostics.primary_range.start;
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                let mut new_selection = s.newest_anchor().clone();
+                new_selection.collapse_to(primary_range_start, SelectionGoal::None);
+                s.select_anchors(vec![new_selection.clone()]);
+            });
+            self.refresh_inline_completion(false, true, cx);
+        }
+    }
+
     fn go_to_next_hunk(&mut self, _: &GoToHunk, cx: &mut ViewContext<Self>) {
         let snapshot = self.snapshot(cx);
         let selection = self.selections.newest::<Point>(cx);
@@ -9445,6 +10370,151 @@ impl Editor {
         }
     }
 
+    fn go_to_next_change(&mut self, _: &GoToNextChange, cx: &mut ViewContext<Self>) {
+        let head_row = self.selections.newest::<Point>(cx).head().row;
+        let hunks = self.unsaved_hunks(cx);
+        let target = hunks
+            .iter()
+            .find(|rows| rows.start.0 > head_row)
+            .or_else(|| hunks.first());
+        if let Some(rows) = target {
+            self.go_to_change(rows.start, cx);
+        }
+    }
+
+    fn go_to_previous_change(&mut self, _: &GoToPreviousChange, cx: &mut ViewContext<Self>) {
+        let head_row = self.selections.newest::<Point>(cx).head().row;
+        let hunks = self.unsaved_hunks(cx);
+        let target = hunks
+            .iter()
+            .rev()
+            .find(|rows| rows.start.0 < head_row)
+            .or_else(|| hunks.last());
+        if let Some(rows) = target {
+            self.go_to_change(rows.start, cx);
+        }
+    }
+
+    fn go_to_change(&mut self, row: MultiBufferRow, cx: &mut ViewContext<Self>) {
+        let point = Point::new(row.0, 0);
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges([point..point]);
+        });
+    }
+
+    /// Returns the rows that changed since each buffer was last saved, as
+    /// line-granular hunks in multibuffer coordinates.
+    pub fn unsaved_hunks(&self, cx: &AppContext) -> Vec<Range<MultiBufferRow>> {
+        self.unsaved_changes(MultiBufferRow::MIN..MultiBufferRow::MAX, cx)
+            .into_iter()
+            .map(|(rows, _)| rows)
+            .collect()
+    }
+
+    /// Returns the kind of change for each row in `rows` that changed since its
+    /// buffer was last saved. Deletions are reported on the row following them.
+    pub fn change_markers(
+        &self,
+        rows: Range<MultiBufferRow>,
+        cx: &AppContext,
+    ) -> Vec<(MultiBufferRow, ChangeKind)> {
+        let mut markers = Vec::new();
+        for (hunk_rows, kind) in self.unsaved_changes(rows.clone(), cx) {
+            if kind == ChangeKind::Deleted {
+                markers.push((hunk_rows.start, kind));
+            } else {
+                let start = hunk_rows.start.max(rows.start);
+                let end = hunk_rows.end.min(rows.end);
+                markers.extend((start.0..end.0).map(|row| (MultiBufferRow(row), kind)));
+            }
+        }
+        markers
+    }
+
+    /// Returns the unsaved hunks intersecting `rows`. Each buffer is diffed
+    /// against its saved version at most once per version, no matter how many
+    /// excerpts it has.
+    fn unsaved_changes(
+        &self,
+        rows: Range<MultiBufferRow>,
+        cx: &AppContext,
+    ) -> Vec<(Range<MultiBufferRow>, ChangeKind)> {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut cache = self.unsaved_hunks_cache.borrow_mut();
+        cache.retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+        let mut hunks = Vec::new();
+        for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let context_start_row = excerpt_range.context.start.to_point(buffer_snapshot).row;
+            let context_end_row = excerpt_range.context.end.to_point(buffer_snapshot).row;
+            let Some(excerpt_start_row) = snapshot
+                .anchor_in_excerpt(excerpt_id, excerpt_range.context.start)
+                .map(|anchor| anchor.to_point(&snapshot).row)
+            else {
+                continue;
+            };
+            let excerpt_end_row = excerpt_start_row + context_end_row - context_start_row;
+            if excerpt_end_row < rows.start.0 || excerpt_start_row >= rows.end.0 {
+                continue;
+            }
+
+            let saved_version = buffer.read(cx).saved_version();
+            let buffer_hunks = match cache.get(&buffer_snapshot.remote_id()) {
+                Some(cached)
+                    if cached.version == buffer_snapshot.version
+                        && &cached.saved_version == saved_version =>
+                {
+                    cached.hunks.clone()
+                }
+                _ => {
+                    let hunks: Arc<[_]> = buffer_snapshot.diff_since(saved_version).into();
+                    cache.insert(
+                        buffer_snapshot.remote_id(),
+                        UnsavedHunks {
+                            version: buffer_snapshot.version.clone(),
+                            saved_version: saved_version.clone(),
+                            hunks: hunks.clone(),
+                        },
+                    );
+                    hunks
+                }
+            };
+
+            for hunk in buffer_hunks.iter() {
+                if hunk.new.start > context_end_row
+                    || hunk.new.end < context_start_row
+                    || (hunk.new.end == context_start_row && !hunk.new.is_empty())
+                {
+                    continue;
+                }
+                let start_row = hunk.new.start.max(context_start_row);
+                let end_row = hunk.new.end.min(context_end_row + 1);
+                let start = excerpt_start_row + start_row - context_start_row;
+                let end = start + end_row.saturating_sub(start_row);
+                let kind = if hunk.old.is_empty() {
+                    ChangeKind::Added
+                } else if hunk.new.is_empty() {
+                    ChangeKind::Deleted
+                } else {
+                    ChangeKind::Modified
+                };
+                let intersects_rows = if kind == ChangeKind::Deleted {
+                    (rows.start.0..rows.end.0).contains(&start)
+                } else {
+                    start < rows.end.0 && end > rows.start.0
+                };
+                if intersects_rows {
+                    hunks.push((MultiBufferRow(start)..MultiBufferRow(end), kind));
+                }
+            }
+        }
+        hunks
+    }
+
     pub fn go_to_definition(
         &mut self,
         _: &GoToDefinition,
@@ -10563,18 +11633,38 @@ impl Editor {
         self.end_transaction_at(Instant::now(), cx)
     }
 
-    pub fn start_transaction_at(&mut self, now: Instant, cx: &mut ViewContext<Self>) {
+    /// Like [`Editor::transact`], but labels the transaction so that its undo
+    /// can be described to the user. See [`MultiBuffer::undo_description`].
+    pub fn transact_with_description(
+        &mut self,
+        description: impl Into<Arc<str>>,
+        cx: &mut ViewContext<Self>,
+        update: impl FnOnce(&mut Self, &mut ViewContext<Self>),
+    ) -> Option<TransactionId> {
+        if let Some(transaction_id) = self.start_transaction_at(Instant::now(), cx) {
+            self.buffer.update(cx, |buffer, cx| {
+                buffer.set_transaction_description(transaction_id, description, cx)
+            });
+        }
+        update(self, cx);
+        self.end_transaction_at(Instant::now(), cx)
+    }
+
+    pub fn start_transaction_at(
+        &mut self,
+        now: Instant,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<TransactionId> {
         self.end_selection(cx);
-        if let Some(tx_id) = self
+        let tx_id = self
             .buffer
-            .update(cx, |buffer, cx| buffer.start_transaction_at(now, cx))
-        {
-            self.selection_history
-                .insert_transaction(tx_id, self.selections.disjoint_anchors());
-            cx.emit(EditorEvent::TransactionBegun {
-                transaction_id: tx_id,
-            })
-        }
+            .update(cx, |buffer, cx| buffer.start_transaction_at(now, cx))?;
+        self.selection_history
+            .insert_transaction(tx_id, self.selections.disjoint_anchors());
+        cx.emit(EditorEvent::TransactionBegun {
+            transaction_id: tx_id,
+        });
+        Some(tx_id)
     }
 
     pub fn end_transaction_at(
@@ -10756,12 +11846,11 @@ impl Editor {
     pub fn fold_all(&mut self, _: &actions::FoldAll, cx: &mut ViewContext<Self>) {
         if self.buffer.read(cx).is_singleton() {
             let mut fold_ranges = Vec::new();
-            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
 
-            for row in 0..snapshot.max_row().0 {
-                if let Some(foldable_range) =
-                    self.snapshot(cx).crease_for_buffer_row(MultiBufferRow(row))
-                {
+            for row in 0..display_map.buffer_snapshot.max_row().0 {
+                let row = MultiBufferRow(row);
+                if let Some(foldable_range) = display_map.syntax_crease_for_buffer_row(row) {
                     fold_ranges.push(foldable_range);
                 }
             }
@@ -10780,6 +11869,14 @@ impl Editor {
         }
     }
 
+    /// Folds the smallest multi-line syntax node enclosing `point`.
+    pub fn fold_syntax_node_at(&mut self, point: Point, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        if let Some(crease) = display_map.syntax_crease_for_point(point) {
+            self.fold_creases(vec![crease], true, cx);
+        }
+    }
+
     pub fn fold_function_bodies(
         &mut self,
         _: &actions::FoldFunctionBodies,
@@ -10971,6 +12068,31 @@ impl Editor {
         self.fold_creases(ranges, auto_scroll, cx);
     }
 
+    /// Folds each of the given ranges, displaying its paired text in place of
+    /// the default ellipsis. Clicking a placeholder unfolds its range.
+    pub fn fold_ranges_with_placeholder<T: ToOffset + Clone>(
+        &mut self,
+        ranges: Vec<(Range<T>, String)>,
+        auto_scroll: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let editor = cx.view().downgrade();
+        let creases = ranges
+            .into_iter()
+            .map(|(range, text)| {
+                let text = SharedString::from(text.replace('\n', " "));
+                Crease::simple(
+                    range,
+                    FoldPlaceholder {
+                        merge_adjacent: false,
+                        ..clickable_fold_placeholder(editor.clone(), Some(text))
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        self.fold_creases(creases, auto_scroll, cx);
+    }
+
     pub fn fold_creases<T: ToOffset + Clone>(
         &mut self,
         creases: Vec<Crease<T>>,
@@ -11249,6 +12371,31 @@ impl Editor {
         Some(text.to_string())
     }
 
+    /// Returns the offset ranges of all selections, including any pending selection, in
+    /// document order.
+    pub fn selected_ranges(&self, cx: &mut AppContext) -> Vec<Range<usize>> {
+        self.selections
+            .all::<usize>(cx)
+            .into_iter()
+            .map(|selection| selection.range())
+            .collect()
+    }
+
+    /// Returns the text of all selections, including any pending selection, in document
+    /// order and separated by newlines.
+    pub fn selected_text(&self, cx: &mut AppContext) -> String {
+        let ranges = self.selected_ranges(cx);
+        let buffer = self.buffer.read(cx).read(cx);
+        let mut text = String::new();
+        for (ix, range) in ranges.into_iter().enumerate() {
+            if ix > 0 {
+                text.push('\n');
+            }
+            text.extend(buffer.text_for_range(range));
+        }
+        text
+    }
+
     pub fn set_text(&mut self, text: impl Into<Arc<str>>, cx: &mut ViewContext<Self>) {
         self.transact(cx, |this, cx| {
             this.buffer
@@ -11259,6 +12406,31 @@ impl Editor {
         });
     }
 
+    /// Replaces the editor's text with `new_text`, such as a formatter's output.
+    /// The texts are diffed in the background and only the changed hunks are
+    /// edited, so that selections and folds between them stay where they are.
+    pub fn apply_formatted_text(
+        &mut self,
+        new_text: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<()> {
+        let buffer = self
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .expect("you can only call apply_formatted_text on editors for singleton buffers");
+        let diff = buffer.read(cx).diff(new_text, cx);
+        cx.spawn(|this, mut cx| async move {
+            let diff = diff.await;
+            this.update(&mut cx, |this, cx| {
+                this.transact(cx, |_, cx| {
+                    buffer.update(cx, |buffer, cx| buffer.apply_diff(diff, cx));
+                });
+            })
+            .ok();
+        })
+    }
+
     pub fn display_text(&self, cx: &mut AppContext) -> String {
         self.display_map
             .update(cx, |map, cx| map.snapshot(cx))
@@ -11435,6 +12607,25 @@ impl Editor {
         cx.notify();
     }
 
+    /// Paints markers for rows changed since the last save in the gutter,
+    /// in place of the git diff hunks.
+    pub fn set_show_change_markers(
+        &mut self,
+        show_change_markers: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.show_change_markers = show_change_markers;
+        cx.notify();
+    }
+
+    pub fn show_change_markers(&self) -> bool {
+        self.show_change_markers
+    }
+
+    pub fn toggle_change_markers(&mut self, _: &ToggleChangeMarkers, cx: &mut ViewContext<Self>) {
+        self.set_show_change_markers(!self.show_change_markers, cx);
+    }
+
     pub fn set_show_code_actions(&mut self, show_code_actions: bool, cx: &mut ViewContext<Self>) {
         self.show_code_actions = Some(show_code_actions);
         cx.notify();
@@ -12446,6 +13637,11 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                if let Some(undo_group_interval) = self.undo_group_interval {
+                    buffer.update(cx, |buffer, _| {
+                        buffer.set_group_interval(undo_group_interval)
+                    });
+                }
                 let buffer_id = buffer.read(cx).remote_id();
                 if !self.diff_map.diff_bases.contains_key(&buffer_id) {
                     if let Some(project) = &self.project {
@@ -12507,6 +13703,23 @@ impl Editor {
         cx.notify();
     }
 
+    fn apply_undo_group_interval(&mut self, cx: &mut ViewContext<Self>) {
+        let undo_group_interval = EditorSettings::get_global(cx)
+            .undo_group_interval
+            .map(Duration::from_millis);
+        if undo_group_interval == self.undo_group_interval {
+            return;
+        }
+
+        // Once the setting is cleared, restore the default interval rather than leaving
+        // the buffers with the last configured one.
+        let group_interval = undo_group_interval.unwrap_or(text::DEFAULT_GROUP_INTERVAL);
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.set_group_interval(group_interval, cx)
+        });
+        self.undo_group_interval = undo_group_interval;
+    }
+
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.tasks_update_task = Some(self.refresh_runnables(cx));
         self.refresh_inline_completion(true, false, cx);
@@ -12524,6 +13737,8 @@ impl Editor {
         {
             let editor_settings = EditorSettings::get_global(cx);
             self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
+            self.scroll_manager.horizontal_autoscroll_margin =
+                editor_settings.horizontal_autoscroll_margin;
             self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
             self.cursor_shape = editor_settings.cursor_shape.unwrap_or_default();
         }
@@ -12531,6 +13746,7 @@ impl Editor {
         if old_cursor_shape != self.cursor_shape {
             cx.emit(EditorEvent::CursorShapeChanged);
         }
+        self.apply_undo_group_interval(cx);
 
         let project_settings = ProjectSettings::get_global(cx);
         self.serialize_dirty_buffers = project_settings.session.restore_unsaved_buffers;
@@ -13208,6 +14424,18 @@ fn get_unstaged_changes_for_buffers(
     .detach();
 }
 
+/// Returns the first run of ASCII digits in `line` as a key that orders
+/// numerically, regardless of how many digits the number has.
+fn first_number_in_line(line: &str) -> Option<(usize, &str)> {
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let digits = &line[start..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    let digits = digits[..end].trim_start_matches('0');
+    Some((digits.len(), digits))
+}
+
 fn char_len_with_expanded_tabs(offset: usize, text: &str, tab_size: NonZeroU32) -> usize {
     let tab_size = tab_size.get() as usize;
     let mut width = offset;
@@ -14060,6 +15288,48 @@ impl SemanticsProvider for Model<Project> {
     }
 }
 
+/// Creates a [`FoldPlaceholder`] that displays `text`, or an ellipsis when it's
+/// `None`, and unfolds its range when clicked.
+fn clickable_fold_placeholder(
+    editor: WeakView<Editor>,
+    text: Option<SharedString>,
+) -> FoldPlaceholder {
+    let label = text.clone().unwrap_or_else(|| "⋯".into());
+    FoldPlaceholder {
+        constrain_width: true,
+        render: Arc::new(move |fold_id, fold_range, cx| {
+            let editor = editor.clone();
+            div()
+                .id(fold_id)
+                .bg(cx.theme().colors().ghost_element_background)
+                .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                .active(|style| style.bg(cx.theme().colors().ghost_element_active))
+                .rounded_sm()
+                .size_full()
+                .cursor_pointer()
+                .child(label.clone())
+                .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                .on_click(move |_, cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.unfold_ranges(
+                                &[fold_range.start..fold_range.end],
+                                true,
+                                false,
+                                cx,
+                            );
+                            cx.stop_propagation();
+                        })
+                        .ok();
+                })
+                .into_any()
+        }),
+        merge_adjacent: true,
+        text,
+        ..Default::default()
+    }
+}
+
 fn inlay_hint_settings(
     location: Anchor,
     snapshot: &MultiBufferSnapshot,
@@ -14070,6 +15340,82 @@ fn inlay_hint_settings(
     language_settings(language, file, cx).inlay_hints
 }
 
+/// Returns the ranges of the quotes enclosing `range`. The innermost string node
+/// delimited by `quote` is used when there is one. Otherwise, the unescaped quotes
+/// from the start of the line decide whether `range` is inside a quoted string.
+fn enclosing_quote_ranges(
+    snapshot: &MultiBufferSnapshot,
+    range: Range<usize>,
+    quote: char,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let quote_len = quote.len_utf8();
+    let mut node_range = range.clone();
+    while let Some((node, ancestor_range)) = snapshot.syntax_ancestor(node_range) {
+        let kind = node.kind();
+        if (kind.contains("string") || kind.contains("char"))
+            && ancestor_range.start + quote_len <= range.start
+            && range.end + quote_len <= ancestor_range.end
+            && snapshot.chars_at(ancestor_range.start).next() == Some(quote)
+            && snapshot.reversed_chars_at(ancestor_range.end).next() == Some(quote)
+        {
+            return Some((
+                ancestor_range.start..ancestor_range.start + quote_len,
+                ancestor_range.end - quote_len..ancestor_range.end,
+            ));
+        }
+        node_range = ancestor_range;
+    }
+
+    let start_row = range.start.to_point(snapshot).row;
+    let end_row = range.end.to_point(snapshot).row;
+    let line_start = snapshot.point_to_offset(Point::new(start_row, 0));
+    let line_end = snapshot.point_to_offset(Point::new(
+        end_row,
+        snapshot.line_len(MultiBufferRow(end_row)),
+    ));
+
+    let mut open_start = None;
+    let mut escaped = false;
+    let mut offset = line_start;
+    for ch in snapshot.chars_at(line_start) {
+        if offset >= line_end {
+            break;
+        }
+        let is_quote = !escaped && ch == quote;
+        escaped = !escaped && ch == '\\';
+        if is_quote {
+            if offset >= range.end {
+                let open_start = open_start?;
+                return Some((
+                    open_start..open_start + quote_len,
+                    offset..offset + quote_len,
+                ));
+            } else if offset < range.start {
+                open_start = match open_start {
+                    Some(_) => None,
+                    None => Some(offset),
+                };
+            } else {
+                return None;
+            }
+        }
+        offset += ch.len_utf8();
+    }
+    None
+}
+
+fn invert_case(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut t, c| {
+            if c.is_uppercase() {
+                t.extend(c.to_lowercase());
+            } else {
+                t.extend(c.to_uppercase());
+            }
+            t
+        })
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -14365,6 +15711,9 @@ pub enum EditorEvent {
     },
     Reloaded,
     CursorShapeChanged,
+    MaxCursorsReached {
+        max_cursors: usize,
+    },
 }
 
 impl EventEmitter<EditorEvent> for Editor {}
@@ -19,11 +19,15 @@ pub struct EditorSettings {
     pub vertical_scroll_margin: f32,
     pub autoscroll_on_clicks: bool,
     pub horizontal_scroll_margin: f32,
+    pub horizontal_autoscroll: bool,
+    pub horizontal_autoscroll_margin: f32,
     pub scroll_sensitivity: f32,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
     pub use_smartcase_search: bool,
     pub multi_cursor_modifier: MultiCursorModifier,
+    pub max_cursors: usize,
+    pub undo_group_interval: Option<u64>,
     pub redact_private_values: bool,
     pub expand_excerpt_lines: u32,
     pub middle_click_paste: bool,
@@ -297,6 +301,7 @@ pub struct EditorSettingsContent {
     /// Default: one_page
     pub scroll_beyond_last_line: Option<ScrollBeyondLastLine>,
     /// The number of lines to keep above/below the cursor when auto-scrolling.
+    /// Margins larger than half the viewport keep the cursor vertically centered.
     ///
     /// Default: 3.
     pub vertical_scroll_margin: Option<f32>,
@@ -308,6 +313,15 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 5.
     pub horizontal_scroll_margin: Option<f32>,
+    /// Whether to scroll horizontally to keep the cursor visible. Horizontal
+    /// autoscroll is always skipped when lines are soft wrapped to the editor width.
+    ///
+    /// Default: true
+    pub horizontal_autoscroll: Option<bool>,
+    /// The number of characters to keep on either side of the cursor when auto-scrolling.
+    ///
+    /// Default: 3.
+    pub horizontal_autoscroll_margin: Option<f32>,
     /// Scroll sensitivity multiplier. This multiplier is applied
     /// to both the horizontal and vertical delta values while scrolling.
     ///
@@ -326,6 +340,16 @@ pub struct EditorSettingsContent {
     ///
     /// Default: alt
     pub multi_cursor_modifier: Option<MultiCursorModifier>,
+    /// The maximum number of cursors that commands such as "select all matches"
+    /// or "add selection below" will create.
+    ///
+    /// Default: 10000
+    pub max_cursors: Option<usize>,
+    /// The maximum time in milliseconds between edits for them to be undone
+    /// together. Set to 0 to undo each edit separately.
+    ///
+    /// Default: None, which groups edits made within 300ms of each other
+    pub undo_group_interval: Option<u64>,
     /// Hide the values of variables in `private` files, as defined by the
     /// private_files setting. This only changes the visual representation,
     /// the values are still present in the file and can be selected / copied / pasted
@@ -32,7 +32,12 @@ use project::{
     project_settings::{LspSettings, ProjectSettings},
 };
 use serde_json::{self, json};
-use std::{cell::RefCell, future::Future, rc::Rc, time::Instant};
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+    time::Instant,
+};
 use std::{
     iter,
     sync::atomic::{self, AtomicUsize},
@@ -168,6 +173,46 @@ fn test_edit_events(cx: &mut TestAppContext) {
     assert_eq!(mem::take(&mut *events.borrow_mut()), []);
 }
 
+#[gpui::test]
+async fn test_reparsed_events(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer =
+        cx.new_model(|cx| language::Buffer::local("fn a() {}", cx).with_language(rust_lang(), cx));
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let multi_buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let (editor, cx) = cx.add_window_view({
+        let events = events.clone();
+        |cx| {
+            let view = cx.view().clone();
+            cx.subscribe(&view, move |_, _, event: &EditorEvent, _| {
+                if let EditorEvent::Reparsed(buffer_id) = event {
+                    events.borrow_mut().push(*buffer_id);
+                }
+            })
+            .detach();
+            build_editor(multi_buffer, cx)
+        }
+    });
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+    events.borrow_mut().clear();
+
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([8..8]));
+        editor.handle_input(" b(); ", cx);
+    });
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+    cx.run_until_parked();
+    assert!(!events.borrow().is_empty());
+    assert!(events.borrow().iter().all(|id| *id == buffer_id));
+}
+
 #[gpui::test]
 fn test_undo_redo_with_selection_restoration(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -243,6 +288,190 @@ fn test_undo_redo_with_selection_restoration(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_undo_redo_restores_all_cursors(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, _| buffer.set_group_interval(Duration::ZERO));
+    cx.set_state(indoc! {"
+        ˇaaa
+        bbb
+        ˇccc
+        ddd"});
+
+    cx.update_editor(|editor, cx| editor.handle_input("1", cx));
+    cx.assert_editor_state(indoc! {"
+        1ˇaaa
+        bbb
+        1ˇccc
+        ddd"});
+
+    cx.set_selections_state(indoc! {"
+        1aaa
+        bbbˇ
+        1ccc
+        dˇdˇd"});
+    cx.update_editor(|editor, cx| editor.handle_input("2", cx));
+    cx.assert_editor_state(indoc! {"
+        1aaa
+        bbb2ˇ
+        1ccc
+        d2ˇd2ˇd"});
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        1aaa
+        bbbˇ
+        1ccc
+        dˇdˇd"});
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        ˇaaa
+        bbb
+        ˇccc
+        ddd"});
+
+    cx.update_editor(|editor, cx| editor.redo(&Redo, cx));
+    cx.assert_editor_state(indoc! {"
+        1ˇaaa
+        bbb
+        1ˇccc
+        ddd"});
+
+    cx.update_editor(|editor, cx| editor.redo(&Redo, cx));
+    cx.assert_editor_state(indoc! {"
+        1aaa
+        bbb2ˇ
+        1ccc
+        d2ˇd2ˇd"});
+}
+
+#[gpui::test]
+async fn test_undo_group_interval_setting(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.undo_group_interval = Some(0);
+            });
+        });
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇ");
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("a", cx);
+        editor.handle_input("b", cx);
+    });
+    cx.assert_editor_state("abˇ");
+
+    // Changing the interval doesn't group new edits with ones made before the change.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.undo_group_interval = Some(60_000);
+            });
+        });
+    });
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("c", cx);
+        editor.handle_input("d", cx);
+    });
+    cx.assert_editor_state("abcdˇ");
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("abˇ");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("aˇ");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("ˇ");
+
+    // Clearing the setting restores the default interval, which is zero in tests.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.undo_group_interval = None;
+            });
+        });
+    });
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("e", cx);
+        editor.handle_input("f", cx);
+    });
+    cx.assert_editor_state("efˇ");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("eˇ");
+}
+
+#[gpui::test]
+fn test_undo_group_interval_for_added_buffers(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.undo_group_interval = Some(60_000);
+            });
+        });
+    });
+
+    let multibuffer = cx.new_model(|_| MultiBuffer::new(ReadWrite));
+    let _editor = cx.add_window(|cx| build_editor(multibuffer.clone(), cx));
+
+    let buffer = cx.new_model(|cx| Buffer::local("", cx));
+    multibuffer.update(cx, |multibuffer, cx| {
+        multibuffer.push_excerpts(
+            buffer.clone(),
+            [ExcerptRange {
+                context: 0..0,
+                primary: None,
+            }],
+            cx,
+        );
+    });
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "a")], None, cx);
+        buffer.edit([(1..1, "b")], None, cx);
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "");
+    });
+}
+
+#[gpui::test]
+async fn test_undo_to_checkpoint(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, _| buffer.set_group_interval(Duration::ZERO));
+    cx.set_state("ˇone");
+
+    let checkpoint = cx
+        .update_editor(|editor, cx| editor.transact(cx, |editor, cx| editor.handle_input("a", cx)))
+        .unwrap();
+    cx.set_selections_state("aoˇne");
+    cx.update_editor(|editor, cx| editor.handle_input("b", cx));
+    cx.set_selections_state("aobneˇ");
+    cx.update_editor(|editor, cx| editor.handle_input("c", cx));
+    cx.assert_editor_state("aobnecˇ");
+
+    // Both later transactions are undone, and the cursor goes back to where it
+    // was before the first of them.
+    cx.update_editor(|editor, cx| editor.undo_to_checkpoint(checkpoint, cx));
+    cx.assert_editor_state("aoˇne");
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("ˇone");
+
+    // The checkpoint itself was undone, so there is nothing to undo back to.
+    cx.update_editor(|editor, cx| editor.undo_to_checkpoint(checkpoint, cx));
+    cx.assert_editor_state("ˇone");
+
+    cx.update_editor(|editor, cx| editor.redo(&Redo, cx));
+    cx.assert_editor_state("aˇone");
+}
+
 #[gpui::test]
 fn test_ime_composition(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -446,6 +675,108 @@ fn test_selection_with_mouse(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+fn test_columnar_selection_with_mouse(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("thequickbrownfox\njumpedoverthelazydogs\nab", cx);
+        build_editor(buffer, cx)
+    });
+
+    _ = editor.update(cx, |view, cx| {
+        view.set_wrap_width(Some(140.0.into()), cx);
+        assert_eq!(
+            view.display_text(cx),
+            "thequickbrownfox\njumpedoverthelaz\nydogs\nab"
+        );
+
+        view.select(
+            SelectPhase::BeginColumnar {
+                position: DisplayPoint::new(DisplayRow(0), 3),
+                reset: true,
+                goal_column: 3,
+            },
+            cx,
+        );
+        view.select(
+            SelectPhase::Update {
+                position: DisplayPoint::new(DisplayRow(3), 2),
+                goal_column: 5,
+                scroll_delta: gpui::Point::<f32>::default(),
+            },
+            cx,
+        );
+        view.select(SelectPhase::End, cx);
+    });
+
+    // Each soft-wrapped display row gets its own selection, and rows that end
+    // before the rectangle get a cursor at their end.
+    assert_eq!(
+        editor
+            .update(cx, |view, cx| view.selections.display_ranges(cx))
+            .unwrap(),
+        [
+            DisplayPoint::new(DisplayRow(0), 3)..DisplayPoint::new(DisplayRow(0), 5),
+            DisplayPoint::new(DisplayRow(1), 3)..DisplayPoint::new(DisplayRow(1), 5),
+            DisplayPoint::new(DisplayRow(2), 3)..DisplayPoint::new(DisplayRow(2), 5),
+            DisplayPoint::new(DisplayRow(3), 2)..DisplayPoint::new(DisplayRow(3), 2),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_remote_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("oˇne two\nthree");
+    cx.update_buffer(|buffer, cx| {
+        let selections: Arc<[Selection<text::Anchor>]> = Arc::from([
+            Selection {
+                id: 0,
+                start: buffer.anchor_before(4),
+                end: buffer.anchor_after(7),
+                reversed: false,
+                goal: SelectionGoal::None,
+            },
+            Selection {
+                id: 1,
+                start: buffer.anchor_before(9),
+                end: buffer.anchor_before(9),
+                reversed: false,
+                goal: SelectionGoal::None,
+            },
+        ]);
+        buffer.apply_ops(
+            [language::Operation::UpdateSelections {
+                selections,
+                lamport_timestamp: clock::Lamport {
+                    replica_id: 1,
+                    value: 1,
+                },
+                line_mode: false,
+                cursor_shape: CursorShape::Bar,
+            }],
+            cx,
+        );
+    });
+
+    // The local replica's selections are excluded.
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.remote_selections(cx),
+            [(
+                1,
+                vec![
+                    DisplayPoint::new(DisplayRow(0), 4)..DisplayPoint::new(DisplayRow(0), 7),
+                    DisplayPoint::new(DisplayRow(1), 0)..DisplayPoint::new(DisplayRow(1), 0),
+                ]
+            )]
+        );
+    });
+}
+
 #[gpui::test]
 fn test_multiple_cursor_removal(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -497,6 +828,29 @@ fn test_multiple_cursor_removal(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_selection_merge_behavior(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("abˇc def");
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([2..2, 2..2, 2..5]));
+        assert_eq!(editor.selections.ranges::<usize>(cx), [2..5]);
+
+        editor.set_selection_merge_behavior(MergeBehavior::Keep);
+        editor.change_selections(None, cx, |s| s.select_ranges([2..2, 2..2]));
+        assert_eq!(editor.selections.ranges::<usize>(cx), [2..2, 2..2]);
+
+        editor.move_right(&MoveRight, cx);
+        assert_eq!(editor.selections.ranges::<usize>(cx), [3..3, 3..3]);
+
+        editor.set_selection_merge_behavior(MergeBehavior::Merge);
+        editor.move_right(&MoveRight, cx);
+        assert_eq!(editor.selections.ranges::<usize>(cx), [4..4]);
+    });
+}
+
 #[gpui::test]
 fn test_canceling_pending_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -774,6 +1128,43 @@ async fn test_navigation_history(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_scroll_anchor_round_trip(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(30, 5, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_scroll_position(gpui::Point::new(0., 10.5), cx);
+        let saved_anchor = editor.scroll_anchor();
+        assert_eq!(
+            saved_anchor
+                .anchor
+                .to_point(&editor.buffer().read(cx).snapshot(cx)),
+            Point::new(10, 0)
+        );
+
+        // Insert lines above the viewport and scroll elsewhere.
+        editor.buffer().update(cx, |buffer, cx| {
+            buffer.edit(
+                [(Point::new(0, 0)..Point::new(0, 0), "x\ny\nz\n")],
+                None,
+                cx,
+            );
+        });
+        editor.set_scroll_position(gpui::Point::new(0., 0.), cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 0.));
+
+        // Restoring the anchor scrolls back to the same text, which has moved down.
+        editor.set_scroll_anchor(saved_anchor, cx);
+        assert_eq!(editor.scroll_anchor(), saved_anchor);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 13.5));
+    });
+}
+
 #[gpui::test]
 fn test_cancel(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -919,6 +1310,126 @@ fn test_fold_action(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_fold_syntax_nodes(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let text = "
+        fn a() {
+            b();
+        }
+
+        fn c() {
+            d(
+                1,
+            );
+        }
+    "
+    .unindent();
+    let buffer = cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(rust_lang(), cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update(cx, |editor, cx| {
+        editor.fold_syntax_node_at(Point::new(6, 8), cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "
+                fn a() {
+                    b();
+                }
+
+                fn c() {
+                    d(⋯);
+                }
+            "
+            .unindent(),
+        );
+
+        editor.fold_syntax_node_at(Point::new(1, 4), cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "
+                fn a() {⋯}
+
+                fn c() {
+                    d(⋯);
+                }
+            "
+            .unindent(),
+        );
+
+        editor.unfold_all(&UnfoldAll, cx);
+        assert_eq!(editor.display_text(cx), text);
+
+        editor.fold_all(&FoldAll, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "
+                fn a() {⋯}
+
+                fn c() {⋯}
+            "
+            .unindent(),
+        );
+
+        // Creases inserted into the crease map take precedence over syntax.
+        editor.unfold_all(&UnfoldAll, cx);
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        let range =
+            snapshot.anchor_before(Point::new(4, 8))..snapshot.anchor_after(Point::new(8, 1));
+        editor.insert_creases(Some(Crease::simple(range, FoldPlaceholder::test())), cx);
+        editor.fold_all(&FoldAll, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "
+                fn a() {⋯}
+
+                fn c() {⋯
+            "
+            .unindent(),
+        );
+    });
+}
+
+#[gpui::test]
+fn test_fold_ranges_with_placeholder(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let text = "{\"a\": {\"b\": 1, \"c\": 2, \"d\": 3}, \"e\": [4, 5]}";
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(text, cx);
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.fold_ranges_with_placeholder(
+            vec![
+                (6..30, "{…3 keys…}".to_string()),
+                (37..43, "[…2\nitems…]".to_string()),
+            ],
+            true,
+            cx,
+        );
+        assert_eq!(
+            view.display_text(cx),
+            "{\"a\": {…3 keys…}, \"e\": […2 items…]}"
+        );
+
+        view.unfold_ranges(&[6..30], true, false, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "{\"a\": {\"b\": 1, \"c\": 2, \"d\": 3}, \"e\": […2 items…]}"
+        );
+
+        view.unfold_ranges(&[37..43], true, false, cx);
+        assert_eq!(view.display_text(cx), text);
+    });
+}
+
 #[gpui::test]
 fn test_fold_action_whitespace_sensitive_language(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1390,6 +1901,48 @@ fn test_move_cursor_multibyte(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_move_cursor_across_multiline_fold(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("0123456789\nfn() {\n    x\n}\n0123456789", cx);
+        build_editor(buffer.clone(), cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.fold_creases(
+            vec![Crease::simple(
+                Point::new(1, 6)..Point::new(3, 0),
+                FoldPlaceholder::test(),
+            )],
+            true,
+            cx,
+        );
+        assert_eq!(view.display_text(cx), "0123456789\nfn() {⋯}\n0123456789");
+
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([empty_range(0, 9)]);
+        });
+
+        view.move_down(&MoveDown, cx);
+        assert_eq!(
+            view.selections.display_ranges(cx)[0].head().row(),
+            DisplayRow(1)
+        );
+        view.move_down(&MoveDown, cx);
+        assert_eq!(view.selections.display_ranges(cx), &[empty_range(2, 9)]);
+        assert_eq!(view.selections.newest::<Point>(cx).head(), Point::new(4, 9));
+
+        view.move_up(&MoveUp, cx);
+        assert_eq!(
+            view.selections.display_ranges(cx)[0].head().row(),
+            DisplayRow(1)
+        );
+        view.move_up(&MoveUp, cx);
+        assert_eq!(view.selections.display_ranges(cx), &[empty_range(0, 9)]);
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor_different_line_lengths(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1948,6 +2501,48 @@ async fn test_move_start_of_paragraph_end_of_paragraph(cx: &mut gpui::TestAppCon
     );
 }
 
+#[gpui::test]
+async fn test_paragraph_movement_across_folds(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        ˇfn a() {
+            x
+
+            y
+        }
+
+        fn b() {}"
+    });
+    cx.update_editor(|editor, cx| {
+        editor.fold_ranges(vec![Point::new(0, 8)..Point::new(4, 0)], false, cx)
+    });
+
+    // The blank line inside the fold is skipped.
+    cx.update_editor(|editor, cx| editor.move_to_end_of_paragraph(&MoveToEndOfParagraph, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            x
+
+            y
+        }
+        ˇ
+        fn b() {}"
+    });
+
+    cx.update_editor(|editor, cx| editor.move_to_start_of_paragraph(&MoveToStartOfParagraph, cx));
+    cx.assert_editor_state(indoc! {"
+        ˇfn a() {
+            x
+
+            y
+        }
+
+        fn b() {}"
+    });
+}
+
 #[gpui::test]
 async fn test_scroll_page_up_page_down(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -2093,6 +2688,181 @@ async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_autoscroll_vertical_scroll_margin(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(5, cx);
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 20. * line_height));
+
+    let mut text = "ˇ".to_string();
+    for row in 0..60 {
+        text.push_str(&format!("line {row}\n"));
+    }
+    cx.set_state(&text);
+
+    fn select_row(cx: &mut EditorTestContext, row: u32, expected_scroll_top: f32) {
+        cx.update_editor(|editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+            });
+        });
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.snapshot(cx).scroll_position(),
+                gpui::Point::new(0., expected_scroll_top)
+            );
+        });
+    }
+
+    // The cursor can move freely while it stays five lines away from the edges.
+    select_row(&mut cx, 14, 0.);
+    // Beyond that, the editor scrolls to keep five lines below the cursor...
+    select_row(&mut cx, 15, 1.);
+    select_row(&mut cx, 30, 16.);
+    // ...and five lines above it.
+    select_row(&mut cx, 25, 16.);
+    select_row(&mut cx, 20, 15.);
+
+    // Margins larger than half the viewport keep the cursor centered.
+    cx.update_editor(|editor, cx| editor.set_vertical_scroll_margin(100, cx));
+    select_row(&mut cx, 35, 25.);
+    select_row(&mut cx, 33, 24.);
+}
+
+#[gpui::test]
+async fn test_horizontal_autoscroll_margin(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(400.), 10. * line_height));
+    cx.set_state(&format!("ˇ{}", "a".repeat(200)));
+
+    fn select_column(cx: &mut EditorTestContext, column: u32) -> f32 {
+        cx.update_editor(|editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([Point::new(0, column)..Point::new(0, column)])
+            });
+        });
+        cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().x)
+    }
+
+    // Moving past the right edge scrolls just far enough to keep the margin
+    // to the right of the cursor, so each extra column of margin scrolls one
+    // column further.
+    let mut scroll_lefts = Vec::new();
+    for margin in [3, 10, 17] {
+        cx.update_editor(|editor, cx| editor.set_horizontal_autoscroll_margin(margin, cx));
+        assert_eq!(select_column(&mut cx, 0), 0.);
+        scroll_lefts.push(select_column(&mut cx, 100));
+    }
+    assert!(scroll_lefts[0] > 0.);
+    assert!(scroll_lefts[1] > scroll_lefts[0]);
+    assert!(
+        ((scroll_lefts[2] - scroll_lefts[1]) - (scroll_lefts[1] - scroll_lefts[0])).abs() < 0.01
+    );
+
+    // Moving back within the margin of the left edge scrolls left again.
+    let scroll_left = select_column(&mut cx, 20);
+    assert!(scroll_left < scroll_lefts[2]);
+    assert_eq!(select_column(&mut cx, 0), 0.);
+
+    // Horizontal autoscroll can be disabled.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.horizontal_autoscroll = Some(false);
+            });
+        });
+    });
+    assert_eq!(select_column(&mut cx, 100), 0.);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.horizontal_autoscroll = Some(true);
+            });
+        });
+    });
+    assert_eq!(select_column(&mut cx, 0), 0.);
+
+    // It's skipped when soft wrapping to the editor width.
+    cx.update_editor(|editor, cx| {
+        editor.set_soft_wrap_mode(language_settings::SoftWrap::EditorWidth, cx)
+    });
+    assert_eq!(select_column(&mut cx, 100), 0.);
+}
+
+#[gpui::test]
+async fn test_batch_autoscroll(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇone two three four");
+
+    let notifications = Rc::new(Cell::new(0));
+    let selection_changes = Rc::new(Cell::new(0));
+    let editor = cx.editor.clone();
+    let _subscriptions = cx.update(|cx| {
+        [
+            cx.observe(&editor, {
+                let notifications = notifications.clone();
+                move |_, _| notifications.set(notifications.get() + 1)
+            }),
+            cx.subscribe(&editor, {
+                let selection_changes = selection_changes.clone();
+                move |_, event: &EditorEvent, _| {
+                    if matches!(event, EditorEvent::SelectionsChanged { .. }) {
+                        selection_changes.set(selection_changes.get() + 1);
+                    }
+                }
+            }),
+        ]
+    });
+
+    cx.update_editor(|editor, cx| {
+        editor.request_autoscroll(Autoscroll::fit(), cx);
+        editor.batch_autoscroll(cx, |editor, cx| {
+            for offset in [4, 8, 14] {
+                editor.change_selections(Some(Autoscroll::newest()), cx, |s| {
+                    s.select_ranges([offset..offset])
+                });
+                // Nested batches defer to the outermost one.
+                editor.batch_autoscroll(cx, |editor, cx| {
+                    editor.request_autoscroll(Autoscroll::center(), cx);
+                });
+                assert!(editor.scroll_manager.autoscroll_request() == Some(Autoscroll::fit()));
+            }
+        });
+        assert!(editor.scroll_manager.autoscroll_request() == Some(Autoscroll::center()));
+    });
+    cx.assert_editor_state("one two three ˇfour");
+
+    // The batched selection changes run their side effects once, and notify
+    // as often as a single selection change does.
+    let batched = (notifications.take(), selection_changes.take());
+    assert_eq!(batched.1, 1);
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(Some(Autoscroll::newest()), cx, |s| s.select_ranges([0..0]))
+    });
+    assert_eq!((notifications.take(), selection_changes.take()), batched);
+}
+
 #[gpui::test]
 async fn test_move_page_up_page_down(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -2214,6 +2984,29 @@ async fn test_move_page_up_page_down(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+fn test_page_down_uses_visible_line_count(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(20, 4, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        // Paging moves by one less than the number of visible lines, so that
+        // the last visible line stays on screen.
+        view.set_visible_line_count(5., cx);
+        view.move_page_down(&MovePageDown::default(), cx);
+        assert_eq!(view.selections.display_ranges(cx), &[empty_range(4, 0)]);
+        view.move_page_down(&MovePageDown::default(), cx);
+        assert_eq!(view.selections.display_ranges(cx), &[empty_range(8, 0)]);
+
+        view.set_visible_line_count(3., cx);
+        view.move_page_up(&MovePageUp::default(), cx);
+        assert_eq!(view.selections.display_ranges(cx), &[empty_range(6, 0)]);
+    });
+}
+
 #[gpui::test]
 async fn test_delete_to_beginning_of_line(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -2785,43 +3578,115 @@ async fn test_indent_outdent(cx: &mut gpui::TestAppContext) {
         t«hree
         ˇ» four
     "});
-    cx.update_editor(|e, cx| e.tab(&Tab, cx));
+    cx.update_editor(|e, cx| e.tab(&Tab, cx));
+    cx.assert_editor_state(indoc! {"
+        one two
+            t«hree
+        ˇ» four
+    "});
+
+    cx.update_editor(|e, cx| e.tab_prev(&TabPrev, cx));
+    cx.assert_editor_state(indoc! {"
+        one two
+        t«hree
+        ˇ» four
+    "});
+
+    // Ensure that indenting/outdenting works when the cursor is at column 0.
+    cx.set_state(indoc! {"
+        one two
+        ˇthree
+            four
+    "});
+    cx.update_editor(|e, cx| e.tab(&Tab, cx));
+    cx.assert_editor_state(indoc! {"
+        one two
+            ˇthree
+            four
+    "});
+
+    cx.set_state(indoc! {"
+        one two
+        ˇ    three
+            four
+    "});
+    cx.update_editor(|e, cx| e.tab_prev(&TabPrev, cx));
+    cx.assert_editor_state(indoc! {"
+        one two
+        ˇthree
+            four
+    "});
+}
+
+#[gpui::test]
+async fn test_copy_indent_from_above_line(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Spaces are copied from the line above.
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = 1;
+          let ˇb = 2;
+        ˇlet c = 3;
+        }
+    "});
+    cx.update_editor(|e, cx| e.copy_indent_from_above_line(&CopyIndentFromAboveLine, cx));
     cx.assert_editor_state(indoc! {"
-        one two
-            t«hree
-        ˇ» four
+        fn main() {
+            let a = 1;
+            let ˇb = 2;
+            ˇlet c = 3;
+        }
     "});
 
-    cx.update_editor(|e, cx| e.tab_prev(&TabPrev, cx));
+    // Lines that are already indented at least as much are left alone.
+    cx.update_editor(|e, cx| e.copy_indent_from_above_line(&CopyIndentFromAboveLine, cx));
     cx.assert_editor_state(indoc! {"
-        one two
-        t«hree
-        ˇ» four
+        fn main() {
+            let a = 1;
+            let ˇb = 2;
+            ˇlet c = 3;
+        }
     "});
 
-    // Ensure that indenting/outdenting works when the cursor is at column 0.
+    // Tabs are copied as tabs.
     cx.set_state(indoc! {"
-        one two
-        ˇthree
-            four
+        fn main() {
+        \t\tlet a = 1;
+        \tlet b = «2ˇ»;
+        }
     "});
-    cx.update_editor(|e, cx| e.tab(&Tab, cx));
+    cx.update_editor(|e, cx| e.copy_indent_from_above_line(&CopyIndentFromAboveLine, cx));
     cx.assert_editor_state(indoc! {"
-        one two
-            ˇthree
-            four
+        fn main() {
+        \t\tlet a = 1;
+        \t\tlet b = «2ˇ»;
+        }
     "});
 
+    // Indentation is compared by its displayed width, with tabs four columns wide.
     cx.set_state(indoc! {"
-        one two
-        ˇ    three
-            four
+        fn main() {
+        \tlet a = 1;
+            ˇlet b = 2;
+        \tˇlet c = 3;
+          ˇlet d = 4;
+        \t  let e = 5;
+        ˇ\tlet f = 6;
+        }
     "});
-    cx.update_editor(|e, cx| e.tab_prev(&TabPrev, cx));
+    cx.update_editor(|e, cx| e.copy_indent_from_above_line(&CopyIndentFromAboveLine, cx));
     cx.assert_editor_state(indoc! {"
-        one two
-        ˇthree
-            four
+        fn main() {
+        \tlet a = 1;
+            ˇlet b = 2;
+        \tˇlet c = 3;
+        \tˇlet d = 4;
+        \t  let e = 5;
+        \t  ˇlet f = 6;
+        }
     "});
 }
 
@@ -2921,6 +3786,25 @@ async fn test_indent_outdent_with_hard_tabs(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_newline_and_outdent_with_hard_tabs(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.hard_tabs = Some(true);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // New lines reproduce the previous line's literal indentation.
+    cx.set_state("\toneˇ\n  twoˇ\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("\tone\n\tˇ\n  two\n  ˇ\n");
+
+    // Outdenting lines indented with spaces removes a tab stop's worth of them.
+    cx.set_state("        ˇx\n\t\tˇy\n");
+    cx.update_editor(|e, cx| e.tab_prev(&TabPrev, cx));
+    cx.assert_editor_state("    ˇx\n\tˇy\n");
+}
+
 #[gpui::test]
 fn test_indent_outdent_with_excerpts(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -3034,6 +3918,91 @@ fn test_indent_outdent_with_excerpts(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_replace_selections_with(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("«oneˇ» two «threeˇ» ˇ four «ˇfive»");
+    cx.update_editor(|editor, cx| {
+        editor.replace_selections_with(
+            vec![
+                "1".to_string(),
+                "3".to_string(),
+                "x".to_string(),
+                "".to_string(),
+            ],
+            cx,
+        )
+    });
+    cx.assert_editor_state("«1ˇ» two «3ˇ» «xˇ» four ˇ");
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("«oneˇ» two «threeˇ» ˇ four «ˇfive»");
+
+    // When the counts don't match, every selection receives all of the texts.
+    cx.set_state("«oneˇ» two «threeˇ»");
+    cx.update_editor(|editor, cx| {
+        editor.replace_selections_with(vec!["a".to_string(), "b".to_string(), "c".to_string()], cx)
+    });
+    cx.assert_editor_state("«a\nb\ncˇ» two «a\nb\ncˇ»");
+}
+
+#[gpui::test]
+async fn test_insert_at_cursors(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        «oneˇ» two
+        «ˇthree» ˇfour
+        fi«veˇ»
+    "});
+    cx.update_editor(|editor, cx| editor.insert_at_cursors("()", cx));
+    cx.assert_editor_state(indoc! {"
+        one()ˇ two
+        ()ˇthree ()ˇfour
+        five()ˇ
+    "});
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        «oneˇ» two
+        «ˇthree» ˇfour
+        fi«veˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_move_selection_to(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("one «twoˇ»\nthree\n");
+    cx.update_editor(|editor, cx| {
+        editor.move_selection_to(DisplayPoint::new(DisplayRow(1), 5), cx)
+    });
+    cx.assert_editor_state("one \nthree«twoˇ»\n");
+
+    // Moving the text is a single transaction.
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("one «twoˇ»\nthree\n");
+
+    // Text can be moved before its original position.
+    cx.set_state("one\nthree «ˇtwo»\n");
+    cx.update_editor(|editor, cx| {
+        editor.move_selection_to(DisplayPoint::new(DisplayRow(0), 0), cx)
+    });
+    cx.assert_editor_state("«twoˇ»one\nthree \n");
+
+    // Targets inside the selection leave the text in place.
+    cx.set_state("one «twoˇ»\nthree\n");
+    cx.update_editor(|editor, cx| {
+        editor.move_selection_to(DisplayPoint::new(DisplayRow(0), 5), cx)
+    });
+    cx.assert_editor_state("one «twoˇ»\nthree\n");
+}
+
 #[gpui::test]
 async fn test_backspace(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3083,6 +4052,30 @@ async fn test_backspace(cx: &mut gpui::TestAppContext) {
         the lazy dogˇ"});
 }
 
+#[gpui::test]
+fn test_empty_edits_at_buffer_edges(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.new_model(|cx| language::Buffer::local("abc", cx));
+    let multi_buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let view = cx.add_window(|cx| build_editor(multi_buffer, cx));
+    let version = buffer.read_with(cx, |buffer, _| buffer.version());
+
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| s.select_ranges([0..0]));
+        view.backspace(&Backspace, cx);
+        view.change_selections(None, cx, |s| s.select_ranges([3..3]));
+        view.delete(&Delete, cx);
+        view.insert("", cx);
+        assert_eq!(view.text(cx), "abc");
+    });
+
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.version(), version);
+        assert!(buffer.peek_undo_stack().is_none());
+    });
+}
+
 #[gpui::test]
 async fn test_delete(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3513,6 +4506,150 @@ async fn test_manipulate_lines_with_single_selection(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_apply_formatted_text(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        fn a() {ˇ}
+        leˇt   x=1;
+        fn b() {ˇ}
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.apply_formatted_text("fn a() {}\nlet x = 1;\nfn b() {}\n".into(), cx)
+    })
+    .await;
+    cx.assert_editor_state(indoc! {"
+        fn a() {ˇ}
+        leˇt x = 1;
+        fn b() {ˇ}
+    "});
+
+    // Applying identical text doesn't create a transaction.
+    cx.update_editor(|editor, cx| {
+        editor.apply_formatted_text("fn a() {}\nlet x = 1;\nfn b() {}\n".into(), cx)
+    })
+    .await;
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {ˇ}
+        leˇt   x=1;
+        fn b() {ˇ}
+    "});
+
+    // Changes at both ends of the buffer don't disturb the cursors between them.
+    cx.set_state(indoc! {"
+        fn a( ) {ˇ}
+        let xˇ = 1;
+        fn b( ) {ˇ}
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.apply_formatted_text("fn a() {}\nlet x = 1;\nfn b() {}\n".into(), cx)
+    })
+    .await;
+    cx.assert_editor_state(indoc! {"
+        fn a() {ˇ}
+        let xˇ = 1;
+        fn b() {ˇ}
+    "});
+}
+
+#[gpui::test]
+async fn test_manipulate_lines_undo_description(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let undo_description = |cx: &mut EditorTestContext| {
+        cx.update_editor(|e, cx| e.buffer().read(cx).undo_description(cx))
+    };
+
+    cx.set_state(indoc! {"
+        «b
+        aˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_case_sensitive(&SortLinesCaseSensitive, cx));
+    assert_eq!(undo_description(&mut cx).as_deref(), Some("Sort Lines"));
+
+    cx.update_editor(|e, cx| e.reverse_lines(&ReverseLines, cx));
+    assert_eq!(undo_description(&mut cx).as_deref(), Some("Reverse Lines"));
+
+    cx.update_editor(|e, cx| e.undo(&Undo, cx));
+    assert_eq!(undo_description(&mut cx).as_deref(), Some("Sort Lines"));
+
+    // Plain edits aren't labeled
+    cx.update_editor(|e, cx| e.handle_input("c", cx));
+    assert_eq!(undo_description(&mut cx), None);
+}
+
+#[gpui::test]
+async fn test_sort_lines_numeric(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        «item2
+        item10
+        item1ˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_numeric(&SortLinesNumeric::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        «item1
+        item2
+        item10ˇ»
+    "});
+
+    // The sort is undone in a single step
+    cx.update_editor(|e, cx| e.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        «item2
+        item10
+        item1ˇ»
+    "});
+
+    // Lines without numbers go first by default, keeping their relative order
+    cx.set_state(indoc! {"
+        «v0010
+        b
+        v9
+        a
+        v011ˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_numeric(&SortLinesNumeric::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        «b
+        a
+        v9
+        v0010
+        v011ˇ»
+    "});
+
+    cx.set_state(indoc! {"
+        «v0010
+        b
+        v9
+        a
+        v011ˇ»
+    "});
+    cx.update_editor(|e, cx| {
+        e.sort_lines_numeric(
+            &SortLinesNumeric {
+                numberless_lines_last: true,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        «v9
+        v0010
+        v011
+        b
+        aˇ»
+    "});
+}
+
 #[gpui::test]
 async fn test_unique_lines_multi_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3792,6 +4929,44 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_toggle_case(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        «Hello Worldˇ»
+        ab«ˇcD»e
+    "});
+    cx.update_editor(|e, cx| e.toggle_case(&ToggleCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «hELLO wORLDˇ»
+        ab«ˇCd»e
+    "});
+
+    // Empty selections toggle the next character and move past it.
+    cx.set_state(indoc! {"
+        ˇhello
+        wˇoRLD 1ˇ
+    "});
+    cx.update_editor(|e, cx| e.toggle_case(&ToggleCase, cx));
+    cx.assert_editor_state(indoc! {"
+        Hˇello
+        wOˇRLD 1ˇ
+    "});
+    cx.update_editor(|e, cx| e.toggle_case(&ToggleCase, cx));
+    cx.assert_editor_state(indoc! {"
+        HEˇllo
+        wOrˇLD 1ˇ
+    "});
+
+    // Characters whose case mapping changes their length are handled.
+    cx.set_state("«ßaˇ» ˇb");
+    cx.update_editor(|e, cx| e.toggle_case(&ToggleCase, cx));
+    cx.assert_editor_state("«SSAˇ» Bˇ");
+}
+
 #[gpui::test]
 fn test_duplicate_line(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4581,6 +5756,120 @@ async fn test_clipboard(cx: &mut gpui::TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+fn test_selected_text(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("one two\nthree four\nfive", cx);
+        build_editor(buffer, cx)
+    });
+
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([14..18, 0..3, 4..4]);
+        });
+        assert_eq!(editor.selected_ranges(cx), [0..3, 4..4, 14..18]);
+        assert_eq!(editor.selected_text(cx), "one\n\nfour");
+
+        // A pending selection is reflected, merging with the selections it overlaps.
+        editor.begin_selection(DisplayPoint::new(DisplayRow(1), 0), true, 1, cx);
+        editor.update_selection(
+            DisplayPoint::new(DisplayRow(2), 2),
+            0,
+            gpui::Point::<f32>::default(),
+            cx,
+        );
+        assert_eq!(editor.selected_ranges(cx), [0..3, 4..4, 8..21]);
+        assert_eq!(editor.selected_text(cx), "one\n\nthree four\nfi");
+    });
+}
+
+#[gpui::test]
+async fn test_clipboard_block(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Copy a columnar block spanning three rows.
+    cx.set_state(indoc! {"
+        a«bcˇ»d
+        e«fgˇ»h
+        i«jkˇ»l"});
+    cx.update_editor(|e, cx| e.copy(&Copy, cx));
+    let clipboard_selections = cx
+        .read_from_clipboard()
+        .and_then(|item| match item.entries().first() {
+            Some(ClipboardEntry::String(string)) => {
+                string.metadata_json::<Vec<ClipboardSelection>>()
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert!(clipboard_selections.iter().all(|s| s.is_block));
+
+    // Pasting into a single cursor re-assembles the block vertically, padding
+    // short lines and appending rows past the end of the buffer.
+    cx.set_state(indoc! {"
+        one ˇtwo
+        x
+        three four"});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        one bcˇtwo
+        x   fg
+        threjke four"});
+
+    cx.set_state("oneˇ");
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state("onebc\n   fg\n   jkˇ");
+
+    // A selection spanning rows is replaced before the block is pasted.
+    cx.set_state(indoc! {"
+        one «two
+        x
+        threˇ»e four
+        six
+        seven"});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        one bcˇe four
+        six fg
+        sevejkn"});
+
+    // Columns are counted in characters, with tabs expanded.
+    cx.set_state("é\tˇx\nñañ\n\tyz");
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state("é\tbcˇx\nñañ fg\n\tjkyz");
+
+    // Pasting into as many cursors as were copied distributes one slice to each.
+    cx.set_state(indoc! {"
+        ˇ1
+        ˇ2
+        ˇ3"});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        bcˇ1
+        fgˇ2
+        jkˇ3"});
+
+    // Selections on the same row aren't a block, and clipboard metadata without
+    // the `is_block` field is still accepted.
+    cx.set_state("«oneˇ» «twoˇ»");
+    cx.update_editor(|e, cx| e.copy(&Copy, cx));
+    cx.set_state("ˇ");
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state("one\ntwoˇ");
+
+    cx.write_to_clipboard(ClipboardItem::new_string_with_metadata(
+        "ab\ncd".into(),
+        r#"[{"len":2,"is_entire_line":false,"first_line_indent":0},{"len":2,"is_entire_line":false,"first_line_indent":0}]"#.into(),
+    ));
+    cx.set_state("ˇ");
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state("ab\ncdˇ");
+}
+
 #[gpui::test]
 async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4712,6 +6001,34 @@ fn test_select_all(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_invert_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("one «twoˇ» three «ˇfour» five");
+    cx.update_editor(|editor, cx| editor.invert_selections(&InvertSelections, cx));
+    cx.assert_editor_state("«one ˇ»two« three ˇ»four« fiveˇ»");
+
+    cx.update_editor(|editor, cx| editor.invert_selections(&InvertSelections, cx));
+    cx.assert_editor_state("one «twoˇ» three «fourˇ» five");
+
+    // Cursors split the inverted selection at their position.
+    cx.set_state("«oneˇ» twˇo\nthree");
+    cx.update_editor(|editor, cx| editor.invert_selections(&InvertSelections, cx));
+    cx.assert_editor_state("one« twˇ»«o\nthreeˇ»");
+
+    cx.set_state("ˇone tˇwo");
+    cx.update_editor(|editor, cx| editor.invert_selections(&InvertSelections, cx));
+    cx.assert_editor_state("«one tˇ»«woˇ»");
+
+    // Inverting a selection of the entire buffer leaves a cursor behind.
+    cx.set_state("«one\ntwoˇ»");
+    cx.update_editor(|editor, cx| editor.invert_selections(&InvertSelections, cx));
+    cx.assert_editor_state("one\ntwoˇ");
+}
+
 #[gpui::test]
 fn test_select_line(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4759,6 +6076,39 @@ fn test_select_line(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_expand_to_line(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        on«e tˇ»wo
+        th«ˇree
+        fo»ur
+        «five
+        ˇ»six"
+    });
+
+    cx.update_editor(|editor, cx| editor.expand_to_line(&ExpandToLine, cx));
+    cx.assert_editor_state(indoc! {"
+        «one twoˇ»
+        «ˇthree
+        four»
+        «fiveˇ»
+        six"
+    });
+
+    // Expanding again doesn't grow the selections any further.
+    cx.update_editor(|editor, cx| editor.expand_to_line(&ExpandToLine, cx));
+    cx.assert_editor_state(indoc! {"
+        «one twoˇ»
+        «ˇthree
+        four»
+        «fiveˇ»
+        six"
+    });
+}
+
 #[gpui::test]
 fn test_split_selection_into_lines(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4832,6 +6182,62 @@ fn test_split_selection_into_lines(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_align_cursors(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        let ˇa = 1;
+        let ˇbcd = 2;
+        let ˇef = 3;
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.align_cursors(
+            &AlignCursors {
+                character: Some('='),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        let a   ˇ= 1;
+        let bcd ˇ= 2;
+        let ef  ˇ= 3;
+    "});
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        let ˇa = 1;
+        let ˇbcd = 2;
+        let ˇef = 3;
+    "});
+
+    cx.set_state(indoc! {"
+        aˇb
+        abcˇd
+        ˇe
+    "});
+    cx.update_editor(|editor, cx| editor.align_cursors(&AlignCursors::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        a  ˇb
+        abcˇd
+           ˇe
+    "});
+
+    // Tabs count as the columns they're displayed in.
+    cx.set_state(indoc! {"
+        \taˇb
+        abcdefˇg
+    "});
+    cx.update_editor(|editor, cx| editor.align_cursors(&AlignCursors::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        \ta ˇb
+        abcdefˇg
+    "});
+}
+
 #[gpui::test]
 async fn test_add_selection_above_below(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -5127,6 +6533,71 @@ async fn test_select_all_matches(cx: &mut gpui::TestAppContext) {
     cx.assert_editor_state("«abcˇ»\n«abcˇ» «abcˇ»\ndefabc\n«abcˇ»");
 }
 
+#[gpui::test]
+async fn test_max_cursors(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.max_cursors = Some(3);
+            });
+        });
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let limit_events = Rc::new(RefCell::new(Vec::new()));
+    cx.update_editor(|_, cx| {
+        let limit_events = limit_events.clone();
+        cx.subscribe(&cx.view().clone(), move |_, _, event: &EditorEvent, _| {
+            if let EditorEvent::MaxCursorsReached { max_cursors } = event {
+                limit_events.borrow_mut().push(*max_cursors);
+            }
+        })
+        .detach();
+    });
+
+    cx.set_state("abc\nˇabc abc\ndefabc\nabc");
+    cx.update_editor(|e, cx| e.select_all_matches(&SelectAllMatches, cx))
+        .unwrap();
+    cx.assert_editor_state("«abcˇ»\n«abcˇ» «abcˇ»\ndefabc\nabc");
+    assert_eq!(mem::take(&mut *limit_events.borrow_mut()), [3]);
+
+    cx.set_state("ˇabc abc abc abc");
+    for _ in 0..3 {
+        cx.update_editor(|e, cx| e.select_next(&SelectNext::default(), cx))
+            .unwrap();
+    }
+    cx.assert_editor_state("«abcˇ» «abcˇ» «abcˇ» abc");
+    assert!(limit_events.borrow().is_empty());
+
+    cx.update_editor(|e, cx| e.select_next(&SelectNext::default(), cx))
+        .unwrap();
+    cx.assert_editor_state("«abcˇ» «abcˇ» «abcˇ» abc");
+    assert_eq!(mem::take(&mut *limit_events.borrow_mut()), [3]);
+
+    cx.set_state("ˇa\nb\nc\nd");
+    cx.update_editor(|e, cx| {
+        e.add_selection_below(&AddSelectionBelow, cx);
+        e.add_selection_below(&AddSelectionBelow, cx);
+        e.add_selection_below(&AddSelectionBelow, cx);
+    });
+    cx.assert_editor_state("ˇa\nˇb\nˇc\nd");
+    assert_eq!(mem::take(&mut *limit_events.borrow_mut()), [3]);
+
+    // A selection spanning several rows is split into no more than the limit.
+    cx.set_state("«a\nb\nc\nd\neˇ»");
+    cx.update_editor(|e, cx| e.add_selection_below(&AddSelectionBelow, cx));
+    cx.assert_editor_state("«aˇ»\n«bˇ»\n«cˇ»\nd\ne");
+    assert_eq!(mem::take(&mut *limit_events.borrow_mut()), [3]);
+
+    // Matches that would be filtered out don't count as dropped.
+    cx.set_state("ˇabc abc abc defabc");
+    cx.update_editor(|e, cx| e.select_all_matches(&SelectAllMatches, cx))
+        .unwrap();
+    cx.assert_editor_state("«abcˇ» «abcˇ» «abcˇ» defabc");
+    assert!(limit_events.borrow().is_empty());
+}
+
 #[gpui::test]
 async fn test_select_next_with_multiple_carets(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5480,6 +6951,68 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_enclosing_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.set_state("fn a(b: (uˇ8, u8)) {}");
+    cx.run_until_parked();
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("fn a(b: («u8, u8ˇ»)) {}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("fn a(b: «(u8, u8)ˇ») {}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("fn a(«b: (u8, u8)ˇ») {}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("fn a«(b: (u8, u8))ˇ» {}");
+
+    // There are no more enclosing brackets, so the selection doesn't change.
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("fn a«(b: (u8, u8))ˇ» {}");
+
+    cx.update_editor(|editor, cx| editor.shrink_enclosing_scope(&ShrinkEnclosingScope, cx));
+    cx.assert_editor_state("fn a(«b: (u8, u8)ˇ») {}");
+
+    cx.update_editor(|editor, cx| editor.shrink_enclosing_scope(&ShrinkEnclosingScope, cx));
+    cx.assert_editor_state("fn a(b: «(u8, u8)ˇ») {}");
+
+    cx.update_editor(|editor, cx| editor.shrink_enclosing_scope(&ShrinkEnclosingScope, cx));
+    cx.assert_editor_state("fn a(b: («u8, u8ˇ»)) {}");
+
+    cx.update_editor(|editor, cx| editor.shrink_enclosing_scope(&ShrinkEnclosingScope, cx));
+    cx.assert_editor_state("fn a(b: (uˇ8, u8)) {}");
+}
+
+#[gpui::test]
+async fn test_select_enclosing_scope_in_plain_text(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("(a [b (cˇ)] d} {e}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("(a [b («cˇ»)] d} {e}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("(a [b «(c)ˇ»] d} {e}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("(a [«b (c)ˇ»] d} {e}");
+
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("(a «[b (c)]ˇ» d} {e}");
+
+    // The opening parenthesis is never closed, and the closing brace doesn't match it.
+    cx.update_editor(|editor, cx| editor.select_enclosing_scope(&SelectEnclosingScope, cx));
+    cx.assert_editor_state("(a «[b (c)]ˇ» d} {e}");
+}
+
 #[gpui::test]
 async fn test_autoindent(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5543,6 +7076,80 @@ async fn test_autoindent(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_autoindent_closing_bracket(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Without a language, a closing bracket is inserted as-is and newlines
+    // keep the indentation of the previous line.
+    cx.set_state(indoc! {"
+        fn a() {
+            b();ˇ
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.newline(&Newline, cx);
+        editor.handle_input("}", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            b();
+            }ˇ
+    "});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                brackets: BracketPairConfig {
+                    pairs: vec![BracketPair {
+                        start: "{".to_string(),
+                        end: "}".to_string(),
+                        close: false,
+                        surround: false,
+                        newline: true,
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_indents_query(r#"(_ "{" "}" @end) @indent"#)
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // With indent rules, a newline after an opening bracket indents one level
+    // deeper, and a closing bracket on an otherwise blank line is outdented to
+    // match its opener.
+    cx.set_state(indoc! {"
+        fn a() {ˇ}
+    "});
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| editor.newline(&Newline, cx));
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            ˇ
+        }
+    "});
+
+    cx.set_state(indoc! {"
+        fn a() {
+            b();
+            ˇ
+    "});
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| editor.handle_input("}", cx));
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            b();
+        }ˇ
+    "});
+}
+
 #[gpui::test]
 async fn test_autoindent_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6406,6 +8013,60 @@ async fn test_surround_with_pair(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_surround_selections(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            brackets: BracketPairConfig {
+                pairs: vec![BracketPair {
+                    start: "\"".to_string(),
+                    end: "\"".to_string(),
+                    close: true,
+                    surround: true,
+                    newline: false,
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Typing a bracket start over a non-empty selection surrounds it.
+    cx.set_state("let a = «helloˇ»;");
+    cx.update_editor(|editor, cx| editor.handle_input("\"", cx));
+    cx.assert_editor_state("let a = \"«helloˇ»\";");
+
+    // The action surrounds each non-empty selection with an arbitrary pair.
+    cx.set_state(indoc! {"
+        one «twoˇ» three
+        ˇfour «ˇfive»
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(
+            &SurroundSelections {
+                start: "<<".to_string(),
+                end: ">>".to_string(),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        one <<«twoˇ»>> three
+        ˇfour <<«ˇfive»>>
+    "});
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        one «twoˇ» three
+        ˇfour «ˇfive»
+    "});
+}
+
 #[gpui::test]
 async fn test_delete_autoclose_pair(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6513,6 +8174,72 @@ async fn test_delete_autoclose_pair(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_delete_autoclose_pair_after_leaving_region(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            brackets: BracketPairConfig {
+                pairs: vec![BracketPair {
+                    start: "{".to_string(),
+                    end: "}".to_string(),
+                    close: true,
+                    surround: true,
+                    newline: true,
+                }],
+                ..Default::default()
+            },
+            autoclose_before: "}".to_string(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    ));
+
+    cx.language_registry().add(language.clone());
+    cx.update_buffer(|buffer, cx| {
+        buffer.set_language(Some(language), cx);
+    });
+
+    cx.set_state(
+        &"
+            aˇ
+            bˇ
+        "
+        .unindent(),
+    );
+
+    // While the autoclose region is still active, both brackets are deleted.
+    cx.update_editor(|view, cx| {
+        view.handle_input("{", cx);
+        view.backspace(&Default::default(), cx);
+    });
+    cx.assert_editor_state(
+        &"
+            aˇ
+            bˇ
+        "
+        .unindent(),
+    );
+
+    // Once the cursors leave the region, only the opening bracket is deleted.
+    cx.update_editor(|view, cx| {
+        view.handle_input("{", cx);
+        view.move_left(&MoveLeft, cx);
+        view.move_right(&MoveRight, cx);
+        view.backspace(&Default::default(), cx);
+    });
+    cx.assert_editor_state(
+        &"
+            aˇ}
+            bˇ}
+        "
+        .unindent(),
+    );
+}
+
 #[gpui::test]
 async fn test_always_treat_brackets_as_autoclosed_delete(cx: &mut gpui::TestAppContext) {
     init_test(cx, |settings| {
@@ -6832,6 +8559,38 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_snippet_tabstops_track_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇ\nˇ\n");
+    cx.update_editor(|editor, cx| {
+        let insertion_ranges = editor.selections.ranges::<usize>(cx);
+        let snippet = Snippet::parse("for ${1:i} in $2 {\n\t$0\n}").unwrap();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+    });
+    cx.assert_editor_state("for «iˇ» in  {\n\t\n}\nfor «iˇ» in  {\n\t\n}\n");
+
+    // Tab stops move every cursor together, and keep tracking their text as
+    // it's edited between jumps.
+    cx.update_editor(|editor, cx| editor.handle_input("idx", cx));
+    cx.update_editor(|editor, cx| assert!(editor.move_to_next_snippet_tabstop(cx)));
+    cx.assert_editor_state("for idx in ˇ {\n\t\n}\nfor idx in ˇ {\n\t\n}\n");
+
+    cx.update_editor(|editor, cx| editor.handle_input("0..n", cx));
+    cx.update_editor(|editor, cx| assert!(editor.move_to_prev_snippet_tabstop(cx)));
+    cx.assert_editor_state("for «idxˇ» in 0..n {\n\t\n}\nfor «idxˇ» in 0..n {\n\t\n}\n");
+
+    cx.update_editor(|editor, cx| assert!(editor.move_to_next_snippet_tabstop(cx)));
+    cx.assert_editor_state("for idx in «0..nˇ» {\n\t\n}\nfor idx in «0..nˇ» {\n\t\n}\n");
+
+    cx.update_editor(|editor, cx| assert!(editor.move_to_next_snippet_tabstop(cx)));
+    cx.assert_editor_state("for idx in 0..n {\n\tˇ\n}\nfor idx in 0..n {\n\tˇ\n}\n");
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -10003,40 +11762,393 @@ async fn go_to_prev_overlapping_diagnostic(
     executor.run_until_parked();
 
     cx.update_editor(|editor, cx| {
-        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic, cx);
+        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic::default(), cx);
+    });
+
+    cx.assert_editor_state(indoc! {"
+        fn func(abc def: i32) -> ˇu32 {
+        }
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic::default(), cx);
+    });
+
+    cx.assert_editor_state(indoc! {"
+        fn func(abc ˇdef: i32) -> u32 {
+        }
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic::default(), cx);
+    });
+
+    cx.assert_editor_state(indoc! {"
+        fn func(abcˇ def: i32) -> u32 {
+        }
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic::default(), cx);
+    });
+
+    cx.assert_editor_state(indoc! {"
+        fn func(abc def: i32) -> ˇu32 {
+        }
+    "});
+}
+
+#[gpui::test]
+async fn test_list_and_go_to_diagnostics(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let lsp_store =
+        cx.update_editor(|editor, cx| editor.project.as_ref().unwrap().read(cx).lsp_store());
+
+    cx.set_state(indoc! {"
+        ˇfn func(abc def: i32) -> u32 {
+        }
+    "});
+
+    cx.update(|cx| {
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                        version: None,
+                        diagnostics: vec![
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 25),
+                                    lsp::Position::new(0, 28),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "unexpected type".to_string(),
+                                ..Default::default()
+                            },
+                            lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 8),
+                                    lsp::Position::new(0, 11),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "unknown name".to_string(),
+                                ..Default::default()
+                            },
+                        ],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap()
+        });
+    });
+
+    executor.run_until_parked();
+
+    let diagnostics = cx.update_editor(|editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .diagnostics(cx)
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.range.to_point(&snapshot),
+                    entry.diagnostic.severity,
+                    entry.diagnostic.message,
+                    entry.diagnostic.group_id,
+                )
+            })
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|(range, severity, message, _)| (range.clone(), *severity, message.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                Point::new(0, 8)..Point::new(0, 11),
+                DiagnosticSeverity::ERROR,
+                "unknown name"
+            ),
+            (
+                Point::new(0, 25)..Point::new(0, 28),
+                DiagnosticSeverity::WARNING,
+                "unexpected type"
+            ),
+        ]
+    );
+
+    let warning_group_id = diagnostics[1].3;
+    cx.update_editor(|editor, cx| editor.go_to_diagnostic_group(warning_group_id, cx));
+    cx.assert_editor_state(indoc! {"
+        fn func(abc def: i32) -> ˇu32 {
+        }
+    "});
+    cx.update_editor(|editor, _| {
+        assert_eq!(
+            editor
+                .active_diagnostics
+                .as_ref()
+                .map(|diagnostics| diagnostics.group_id),
+            Some(warning_group_id)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_go_to_diagnostic_with_severity(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let lsp_store =
+        cx.update_editor(|editor, cx| editor.project.as_ref().unwrap().read(cx).lsp_store());
+
+    cx.set_state(indoc! {"
+        ˇfn func(abc def: i32) -> u32 {
+        }
+    "});
+
+    let diagnostic = |column, severity| lsp::Diagnostic {
+        range: lsp::Range::new(
+            lsp::Position::new(0, column),
+            lsp::Position::new(0, column + 3),
+        ),
+        severity: Some(severity),
+        ..Default::default()
+    };
+    cx.update(|cx| {
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                        version: None,
+                        diagnostics: vec![
+                            diagnostic(3, lsp::DiagnosticSeverity::HINT),
+                            diagnostic(8, lsp::DiagnosticSeverity::WARNING),
+                            diagnostic(12, lsp::DiagnosticSeverity::ERROR),
+                            diagnostic(17, lsp::DiagnosticSeverity::WARNING),
+                            diagnostic(25, lsp::DiagnosticSeverity::ERROR),
+                        ],
+                    },
+                    &[],
+                    cx,
+                )
+                .unwrap()
+        });
+    });
+
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor
+                .diagnostic_count_by_severity(cx)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [
+                (DiagnosticSeverity::ERROR, 2),
+                (DiagnosticSeverity::WARNING, 2),
+                (DiagnosticSeverity::HINT, 1),
+            ]
+        );
+    });
+
+    let errors_only = Some(DiagnosticSeverityFilter::Error);
+    cx.update_editor(|editor, cx| {
+        editor.go_to_diagnostic(
+            &GoToDiagnostic {
+                severity: errors_only,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        fn func(abc ˇdef: i32) -> u32 {
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.go_to_diagnostic(
+            &GoToDiagnostic {
+                severity: errors_only,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        fn func(abc def: i32) -> ˇu32 {
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_diagnostic(
+            &GoToPrevDiagnostic {
+                severity: errors_only,
+            },
+            cx,
+        )
     });
+    cx.assert_editor_state(indoc! {"
+        fn func(abc ˇdef: i32) -> u32 {
+        }
+    "});
 
+    // Without an explicit severity, the editor's filter is used, which skips hints by default.
+    cx.update_editor(|editor, cx| editor.go_to_diagnostic(&GoToDiagnostic::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        fn func(abc def: ˇi32) -> u32 {
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.set_diagnostic_severity_filter(DiagnosticSeverityFilter::Hint);
+        editor.go_to_diagnostic(&GoToDiagnostic::default(), cx);
+    });
     cx.assert_editor_state(indoc! {"
         fn func(abc def: i32) -> ˇu32 {
         }
     "});
+    cx.update_editor(|editor, cx| editor.go_to_diagnostic(&GoToDiagnostic::default(), cx));
+    cx.assert_editor_state(indoc! {"
+        fn ˇfunc(abc def: i32) -> u32 {
+        }
+    "});
+}
+
+#[gpui::test]
+async fn test_toggle_diagnostic_at_cursor(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let lsp_store =
+        cx.update_editor(|editor, cx| editor.project.as_ref().unwrap().read(cx).lsp_store());
+
+    cx.set_state(indoc! {"
+        fn func(abc dˇef: i32) -> u32 {
+        }
+    "});
+
+    let publish_diagnostics = |message: &str, cx: &mut EditorTestContext| {
+        let message = message.to_string();
+        cx.update(|cx| {
+            lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store
+                    .update_diagnostics(
+                        LanguageServerId(0),
+                        lsp::PublishDiagnosticsParams {
+                            uri: lsp::Url::from_file_path("/root/file").unwrap(),
+                            version: None,
+                            diagnostics: vec![
+                                lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 12),
+                                        lsp::Position::new(0, 15),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                    message,
+                                    ..Default::default()
+                                },
+                                lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 25),
+                                        lsp::Position::new(0, 28),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                    message: "unexpected type".to_string(),
+                                    ..Default::default()
+                                },
+                            ],
+                        },
+                        &[],
+                        cx,
+                    )
+                    .unwrap()
+            });
+        });
+    };
+    publish_diagnostics("unknown name", &mut cx);
+    executor.run_until_parked();
+
+    let active_diagnostic = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, _| {
+            editor
+                .active_diagnostics
+                .as_ref()
+                .map(|diagnostics| (diagnostics.primary_message.clone(), diagnostics.is_valid))
+        })
+    };
+
+    // Peeking shows the diagnostic without moving the cursor.
+    cx.update_editor(|editor, cx| {
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
+    });
+    assert_eq!(
+        active_diagnostic(&mut cx),
+        Some(("unknown name".to_string(), true))
+    );
+    cx.assert_editor_state(indoc! {"
+        fn func(abc dˇef: i32) -> u32 {
+        }
+    "});
 
     cx.update_editor(|editor, cx| {
-        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic, cx);
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
     });
+    assert_eq!(active_diagnostic(&mut cx), None);
 
-    cx.assert_editor_state(indoc! {"
-        fn func(abc ˇdef: i32) -> u32 {
+    // Peeking elsewhere switches to that diagnostic.
+    cx.update_editor(|editor, cx| {
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
+    });
+    cx.set_selections_state(indoc! {"
+        fn func(abc def: i32) -> uˇ32 {
         }
     "});
-
     cx.update_editor(|editor, cx| {
-        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic, cx);
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
     });
+    assert_eq!(
+        active_diagnostic(&mut cx),
+        Some(("unexpected type".to_string(), true))
+    );
 
-    cx.assert_editor_state(indoc! {"
-        fn func(abcˇ def: i32) -> u32 {
+    // Nothing is shown when the cursor isn't inside of a diagnostic.
+    cx.set_selections_state(indoc! {"
+        fn ˇfunc(abc def: i32) -> u32 {
         }
     "});
-
     cx.update_editor(|editor, cx| {
-        editor.go_to_prev_diagnostic(&GoToPrevDiagnostic, cx);
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
     });
+    assert_eq!(active_diagnostic(&mut cx), None);
 
-    cx.assert_editor_state(indoc! {"
-        fn func(abc def: i32) -> ˇu32 {
+    // A peeked diagnostic is invalidated when it changes underneath it.
+    cx.set_selections_state(indoc! {"
+        fn func(abc dˇef: i32) -> u32 {
         }
     "});
+    cx.update_editor(|editor, cx| {
+        editor.toggle_diagnostic_at_cursor(&ToggleDiagnosticAtCursor, cx)
+    });
+    publish_diagnostics("unknown variable", &mut cx);
+    executor.run_until_parked();
+    assert_eq!(
+        active_diagnostic(&mut cx),
+        Some(("unknown name".to_string(), false))
+    );
 }
 
 #[gpui::test]
@@ -10077,6 +12189,152 @@ async fn test_diagnostics_with_links(cx: &mut TestAppContext) {
     cx.update_editor(|editor, _| assert!(editor.hover_state.diagnostic_popover.is_some()))
 }
 
+#[gpui::test]
+async fn test_go_to_change(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+        five
+        six
+    "});
+    cx.update_buffer(|buffer, cx| buffer.did_save(buffer.version(), None, cx));
+    cx.update_buffer(|buffer, cx| buffer.edit([(4..7, "TWO"), (19..23, "FIVE")], None, cx));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.unsaved_hunks(cx),
+            [
+                MultiBufferRow(1)..MultiBufferRow(2),
+                MultiBufferRow(4)..MultiBufferRow(5)
+            ]
+        );
+    });
+
+    cx.update_editor(|editor, cx| editor.go_to_next_change(&GoToNextChange, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        ˇTWO
+        three
+        four
+        FIVE
+        six
+    "});
+
+    cx.update_editor(|editor, cx| editor.go_to_next_change(&GoToNextChange, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        TWO
+        three
+        four
+        ˇFIVE
+        six
+    "});
+
+    // Wraps around to the first change.
+    cx.update_editor(|editor, cx| editor.go_to_next_change(&GoToNextChange, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        ˇTWO
+        three
+        four
+        FIVE
+        six
+    "});
+
+    // Wraps around to the last change.
+    cx.update_editor(|editor, cx| editor.go_to_previous_change(&GoToPreviousChange, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        TWO
+        three
+        four
+        ˇFIVE
+        six
+    "});
+
+    cx.update_editor(|editor, cx| editor.go_to_previous_change(&GoToPreviousChange, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        ˇTWO
+        three
+        four
+        FIVE
+        six
+    "});
+
+    // Saving clears the changes.
+    cx.update_buffer(|buffer, cx| buffer.did_save(buffer.version(), None, cx));
+    cx.update_editor(|editor, cx| {
+        assert!(editor.unsaved_hunks(cx).is_empty());
+        editor.go_to_next_change(&GoToNextChange, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        one
+        ˇTWO
+        three
+        four
+        FIVE
+        six
+    "});
+}
+
+#[gpui::test]
+async fn test_change_markers(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+        five
+    "});
+    cx.update_editor(|editor, cx| {
+        assert!(!editor.show_change_markers());
+        editor.toggle_change_markers(&ToggleChangeMarkers, cx);
+        assert!(editor.show_change_markers());
+    });
+    cx.update_buffer(|buffer, cx| buffer.did_save(buffer.version(), None, cx));
+    cx.update_buffer(|buffer, cx| {
+        buffer.edit([(4..4, "new\n"), (8..9, "T"), (19..24, "")], None, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        ˇone
+        new
+        two
+        Three
+        four
+    "});
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.change_markers(MultiBufferRow::MIN..MultiBufferRow::MAX, cx),
+            [
+                (MultiBufferRow(1), ChangeKind::Added),
+                (MultiBufferRow(3), ChangeKind::Modified),
+                (MultiBufferRow(5), ChangeKind::Deleted),
+            ]
+        );
+
+        // Only markers within the requested rows are returned.
+        assert_eq!(
+            editor.change_markers(MultiBufferRow(2)..MultiBufferRow(5), cx),
+            [(MultiBufferRow(3), ChangeKind::Modified)]
+        );
+    });
+
+    cx.update_buffer(|buffer, cx| buffer.did_save(buffer.version(), None, cx));
+    cx.update_editor(|editor, cx| {
+        assert!(editor
+            .change_markers(MultiBufferRow::MIN..MultiBufferRow::MAX, cx)
+            .is_empty())
+    });
+}
+
 #[gpui::test]
 async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -10290,6 +12548,165 @@ async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_select_inside_delimiters(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    let mut assert = |before, action, after| {
+        cx.set_state(before);
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| editor.select_inside_delimiters(&action, cx));
+        cx.assert_editor_state(after);
+    };
+    let parens = SelectInsideDelimiters {
+        open: '(',
+        close: ')',
+        include_delimiters: false,
+    };
+    let brackets = SelectInsideDelimiters {
+        open: '[',
+        close: ']',
+        include_delimiters: false,
+    };
+    let quotes = SelectInsideDelimiters {
+        open: '"',
+        close: '"',
+        include_delimiters: false,
+    };
+
+    // The innermost enclosing pair of the requested delimiters is selected.
+    assert(
+        "fn a() { foo(1, bar(2, ˇ3), [4]); }",
+        parens.clone(),
+        "fn a() { foo(1, bar(«2, 3ˇ»), [4]); }",
+    );
+    assert(
+        "fn a() { foo(1, [2, (ˇ3)]); }",
+        brackets.clone(),
+        "fn a() { foo(1, [«2, (3)ˇ»]); }",
+    );
+    assert(
+        "fn a() { foo(1, bar(2, ˇ3)); }",
+        SelectInsideDelimiters {
+            include_delimiters: true,
+            ..parens.clone()
+        },
+        "fn a() { foo(1, bar«(2, 3)ˇ»); }",
+    );
+
+    // Each cursor expands independently.
+    assert(
+        indoc! {"
+            let a = [1, ˇ2];
+            let b = (3, [ˇ4]);
+        "},
+        brackets,
+        indoc! {"
+            let a = [«1, 2ˇ»];
+            let b = (3, [«4ˇ»]);
+        "},
+    );
+
+    // Quotes are matched on the cursor's line.
+    assert(
+        indoc! {r#"
+            let a = "heˇllo";
+            let b = "world";
+        "#},
+        quotes.clone(),
+        indoc! {r#"
+            let a = "«helloˇ»";
+            let b = "world";
+        "#},
+    );
+    assert(
+        r#"let a = "heˇllo";"#,
+        SelectInsideDelimiters {
+            include_delimiters: true,
+            ..quotes.clone()
+        },
+        r#"let a = «"hello"ˇ»;"#,
+    );
+
+    // Quotes come from the string around the cursor, not from neighboring strings,
+    // and escaped quotes don't end the string.
+    assert(
+        r#"fn a() { foo("a", ˇb, "c"); }"#,
+        quotes.clone(),
+        r#"fn a() { foo("a", ˇb, "c"); }"#,
+    );
+    assert(
+        r#"let a = "say \"hˇi\" now";"#,
+        quotes.clone(),
+        r#"let a = "«say \"hi\" nowˇ»";"#,
+    );
+
+    // Selections that aren't enclosed by the delimiters are left alone.
+    assert("let a = ˇb;", parens, "let a = ˇb;");
+
+    // Without a grammar, unescaped quotes are counted from the start of the line.
+    cx.update_buffer(|buffer, cx| buffer.set_language(None, cx));
+    for (before, after) in [
+        (r#""a" x ˇ "b""#, r#""a" x ˇ "b""#),
+        (r#""a" "x ˇy" "b""#, r#""a" "«x yˇ»" "b""#),
+        (r#"say "\"hˇi\"" now"#, r#"say "«\"hi\"ˇ»" now"#),
+    ] {
+        cx.set_state(before);
+        cx.update_editor(|editor, cx| editor.select_inside_delimiters(&quotes, cx));
+        cx.assert_editor_state(after);
+    }
+}
+
+#[gpui::test]
+async fn test_select_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.set_state(indoc! {"
+        fn a() { foo(bar(ˇ1), 2) }
+        fn b() { [ˇ3] }
+    "});
+    cx.run_until_parked();
+
+    // Each invocation extends the head to the next enclosing pair.
+    cx.update_editor(|editor, cx| {
+        editor.select_to_enclosing_bracket(&SelectToEnclosingBracket, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() { foo(bar(«1)ˇ», 2) }
+        fn b() { [«3]ˇ» }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.select_to_enclosing_bracket(&SelectToEnclosingBracket, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() { foo(bar(«1), 2)ˇ» }
+        fn b() { [«3] }ˇ»
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.select_to_enclosing_bracket(&SelectToEnclosingBracket, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() { foo(bar(«1), 2) }ˇ»
+        fn b() { [«3] }ˇ»
+    "});
+
+    // Reversed selections extend towards the opening bracket.
+    cx.set_state("fn a() { foo(bar(«ˇ1»)) }");
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        editor.select_to_enclosing_bracket(&SelectToEnclosingBracket, cx)
+    });
+    cx.assert_editor_state("fn a() { foo(bar«ˇ(1»)) }");
+    cx.update_editor(|editor, cx| {
+        editor.select_to_enclosing_bracket(&SelectToEnclosingBracket, cx)
+    });
+    cx.assert_editor_state("fn a() { foo«ˇ(bar(1»)) }");
+}
+
 #[gpui::test]
 async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -13810,6 +16227,114 @@ async fn test_input_text(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_scroll_cursor_to_center_top_and_bottom(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+
+    let mut text = String::new();
+    for row in 0..40 {
+        if row == 20 {
+            text.push_str("lineˇ 20\n");
+        } else {
+            text.push_str(&format!("line {row}\n"));
+        }
+    }
+    cx.set_state(&text);
+    cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(2, cx);
+        assert_eq!(editor.visible_line_count(), Some(10.));
+    });
+
+    cx.update_editor(|editor, cx| {
+        editor.scroll_cursor_center(&ScrollCursorCenter, cx);
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 15.)
+        );
+    });
+    cx.assert_editor_state(&text);
+
+    cx.update_editor(|editor, cx| {
+        editor.scroll_cursor_top(&ScrollCursorTop, cx);
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 18.)
+        );
+    });
+    cx.assert_editor_state(&text);
+
+    cx.update_editor(|editor, cx| {
+        editor.scroll_cursor_bottom(&ScrollCursorBottom, cx);
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 12.)
+        );
+    });
+    cx.assert_editor_state(&text);
+}
+
+#[gpui::test]
+async fn test_visible_row_range(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+
+    let text = (0..20)
+        .map(|row| format!("line {row}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    cx.set_state(&format!("ˇ{text}"));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.visible_row_range(), DisplayRow(0)..DisplayRow(10));
+        assert_eq!(
+            editor
+                .visible_anchor_range(cx)
+                .to_point(&editor.buffer().read(cx).snapshot(cx)),
+            Point::new(0, 0)..Point::new(10, 0)
+        );
+        editor.set_scroll_position(gpui::Point::new(0., 5.), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.visible_row_range(), DisplayRow(5)..DisplayRow(15));
+        assert_eq!(
+            editor
+                .visible_anchor_range(cx)
+                .to_point(&editor.buffer().read(cx).snapshot(cx)),
+            Point::new(5, 0)..Point::new(15, 0)
+        );
+        editor.set_scroll_position(gpui::Point::new(0., 15.), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.visible_row_range(), DisplayRow(15)..DisplayRow(20));
+        assert_eq!(
+            editor
+                .visible_anchor_range(cx)
+                .to_point(&editor.buffer().read(cx).snapshot(cx)),
+            Point::new(15, 0)..Point::new(19, 7)
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_scroll_cursor_center_top_bottom(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -17,7 +17,7 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
     scroll::{axis_pair, scroll_amount::ScrollAmount, AxisPair},
-    BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
+    BlockId, ChangeKind, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
     EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GutterDimensions, HalfPageDown,
     HalfPageUp, HandleInput, HoveredCursor, HoveredHunk, InlineCompletion, JumpData, LineDown,
@@ -193,10 +193,12 @@ impl EditorElement {
         register_action(view, cx, Editor::indent);
         register_action(view, cx, Editor::outdent);
         register_action(view, cx, Editor::autoindent);
+        register_action(view, cx, Editor::copy_indent_from_above_line);
         register_action(view, cx, Editor::delete_line);
         register_action(view, cx, Editor::join_lines);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
         register_action(view, cx, Editor::sort_lines_case_insensitive);
+        register_action(view, cx, Editor::sort_lines_numeric);
         register_action(view, cx, Editor::reverse_lines);
         register_action(view, cx, Editor::shuffle_lines);
         register_action(view, cx, Editor::convert_to_upper_case);
@@ -207,6 +209,7 @@ impl EditorElement {
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
         register_action(view, cx, Editor::convert_to_opposite_case);
+        register_action(view, cx, Editor::toggle_case);
         register_action(view, cx, Editor::delete_to_previous_word_start);
         register_action(view, cx, Editor::delete_to_previous_subword_start);
         register_action(view, cx, Editor::delete_to_next_word_end);
@@ -284,13 +287,16 @@ impl EditorElement {
         register_action(view, cx, Editor::select_to_beginning);
         register_action(view, cx, Editor::select_to_end);
         register_action(view, cx, Editor::select_all);
+        register_action(view, cx, Editor::invert_selections);
         register_action(view, cx, |editor, action, cx| {
             editor.select_all_matches(action, cx).log_err();
         });
         register_action(view, cx, Editor::select_line);
+        register_action(view, cx, Editor::expand_to_line);
         register_action(view, cx, Editor::split_selection_into_lines);
         register_action(view, cx, Editor::add_selection_above);
         register_action(view, cx, Editor::add_selection_below);
+        register_action(view, cx, Editor::align_cursors);
         register_action(view, cx, |editor, action, cx| {
             editor.select_next(action, cx).log_err();
         });
@@ -299,9 +305,14 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::toggle_comments);
         register_action(view, cx, Editor::select_larger_syntax_node);
+        register_action(view, cx, Editor::select_enclosing_scope);
+        register_action(view, cx, Editor::shrink_enclosing_scope);
         register_action(view, cx, Editor::select_smaller_syntax_node);
         register_action(view, cx, Editor::select_enclosing_symbol);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
+        register_action(view, cx, Editor::select_inside_delimiters);
+        register_action(view, cx, Editor::select_to_enclosing_bracket);
+        register_action(view, cx, Editor::surround_selections);
         register_action(view, cx, Editor::undo_selection);
         register_action(view, cx, Editor::redo_selection);
         if !view.read(cx).is_singleton(cx) {
@@ -311,8 +322,11 @@ impl EditorElement {
         }
         register_action(view, cx, Editor::go_to_diagnostic);
         register_action(view, cx, Editor::go_to_prev_diagnostic);
+        register_action(view, cx, Editor::toggle_diagnostic_at_cursor);
         register_action(view, cx, Editor::go_to_next_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::go_to_next_change);
+        register_action(view, cx, Editor::go_to_previous_change);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -366,6 +380,7 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_line_numbers);
         register_action(view, cx, Editor::toggle_relative_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
+        register_action(view, cx, Editor::toggle_change_markers);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, Editor::toggle_inline_completions);
         register_action(view, cx, hover_popover::hover);
@@ -1585,6 +1600,35 @@ impl EditorElement {
         })
     }
 
+    /// Lays out the unsaved change markers for the given display rows. Only the
+    /// buffer rows backing those display rows are expanded into markers.
+    fn layout_change_markers(
+        &self,
+        display_rows: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Option<Vec<(DisplayRow, ChangeKind)>> {
+        let editor = self.editor.read(cx);
+        if !editor.show_change_markers() {
+            return None;
+        }
+
+        let buffer_start = DisplayPoint::new(display_rows.start, 0).to_point(snapshot);
+        let buffer_end = DisplayPoint::new(display_rows.end, 0).to_point(snapshot);
+        let buffer_rows = MultiBufferRow(buffer_start.row)..MultiBufferRow(buffer_end.row + 1);
+        let markers = editor
+            .change_markers(buffer_rows, cx)
+            .into_iter()
+            .filter_map(|(row, kind)| {
+                let display_row = Point::new(row.0, 0).to_display_point(snapshot).row();
+                display_rows
+                    .contains(&display_row)
+                    .then_some((display_row, kind))
+            })
+            .collect();
+        Some(markers)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_inline_blame(
         &self,
@@ -4068,6 +4112,48 @@ impl EditorElement {
         });
     }
 
+    fn paint_change_markers(layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(change_markers) = layout.change_markers.as_ref() else {
+            return;
+        };
+
+        let line_height = layout.position_map.line_height;
+        let scroll_top = layout.position_map.snapshot.scroll_position().y * line_height;
+        let gutter_origin = layout.gutter_hitbox.bounds.origin;
+        cx.paint_layer(layout.gutter_hitbox.bounds, |cx| {
+            for (row, kind) in change_markers {
+                let start_y = row.as_f32() * line_height - scroll_top;
+                let (bounds, color) = match kind {
+                    ChangeKind::Added | ChangeKind::Modified => (
+                        Bounds::new(
+                            gutter_origin + point(px(0.), start_y),
+                            size(Self::diff_hunk_strip_width(line_height), line_height),
+                        ),
+                        if *kind == ChangeKind::Added {
+                            cx.theme().status().created
+                        } else {
+                            cx.theme().status().modified
+                        },
+                    ),
+                    ChangeKind::Deleted => (
+                        Bounds::new(
+                            gutter_origin + point(px(0.), start_y - line_height / 2.),
+                            size((0.35 * line_height).floor(), line_height),
+                        ),
+                        cx.theme().status().deleted,
+                    ),
+                };
+                cx.paint_quad(quad(
+                    bounds,
+                    Corners::all(px(0.)),
+                    color,
+                    Edges::default(),
+                    transparent_black(),
+                ));
+            }
+        });
+    }
+
     pub(super) fn diff_hunk_bounds(
         snapshot: &EditorSnapshot,
         line_height: Pixels,
@@ -4177,7 +4263,9 @@ impl EditorElement {
                     Some(GitGutterSetting::TrackedFiles)
                 )
             });
-        if show_git_gutter {
+        if layout.change_markers.is_some() {
+            Self::paint_change_markers(layout, cx)
+        } else if show_git_gutter {
             Self::paint_diff_hunks(layout, cx)
         }
 
@@ -6204,6 +6292,9 @@ impl Element for EditorElement {
                         max_row.next_row().0,
                     );
                     let end_row = DisplayRow(end_row);
+                    self.editor.update(cx, |editor, _| {
+                        editor.set_visible_row_range(start_row..end_row)
+                    });
 
                     let buffer_rows = snapshot
                         .buffer_rows(start_row)
@@ -6323,6 +6414,8 @@ impl Element for EditorElement {
                         &snapshot,
                         cx,
                     );
+                    let change_markers =
+                        self.layout_change_markers(start_row..end_row, &snapshot, cx);
 
                     let mut max_visible_line_width = Pixels::ZERO;
                     let mut line_layouts = Self::layout_lines(
@@ -6808,6 +6901,7 @@ impl Element for EditorElement {
                         text_hitbox,
                         gutter_hitbox,
                         display_hunks,
+                        change_markers,
                         content_origin,
                         scrollbars_layout,
                         active_rows,
@@ -7009,6 +7103,7 @@ pub struct EditorLayout {
     line_elements: SmallVec<[AnyElement; 1]>,
     line_numbers: Arc<HashMap<MultiBufferRow, LineNumberLayout>>,
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
+    change_markers: Option<Vec<(DisplayRow, ChangeKind)>>,
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
     blocks: Vec<BlockLayout>,
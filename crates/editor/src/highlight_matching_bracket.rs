@@ -113,6 +113,15 @@ mod tests {
             }
         "#});
 
+        // editing the buffer keeps the highlights on the same brackets
+        cx.update_buffer(|buffer, cx| buffer.edit([(0..0, "// comment\n")], None, cx));
+        cx.assert_editor_background_highlights::<MatchingBracketHighlight>(indoc! {r#"
+            // comment
+            pub fn test("Test argument") {
+                another_test«(»1, 2, 3«)»;
+            }
+        "#});
+
         cx.set_state(indoc! {r#"
             pub fn test("Test argument") {
                 anotherˇ_test(1, 2, 3);
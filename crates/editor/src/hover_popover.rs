@@ -1391,6 +1391,89 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_diagnostic_message_at(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+        cx.set_state(indoc! {"
+            fn ˇtest() { println!(); }
+        "});
+
+        let function_range = cx.text_anchor_range(indoc! {"
+            fn «test() { println!(); }»
+        "});
+        let name_range = cx.text_anchor_range(indoc! {"
+            fn «test»() { println!(); }
+        "});
+        cx.update_buffer(|buffer, cx| {
+            let snapshot = buffer.text_snapshot();
+            let set = DiagnosticSet::from_sorted_entries(
+                vec![
+                    DiagnosticEntry {
+                        range: function_range,
+                        diagnostic: Diagnostic {
+                            severity: DiagnosticSeverity::ERROR,
+                            message: "An error.".to_string(),
+                            group_id: 0,
+                            ..Default::default()
+                        },
+                    },
+                    DiagnosticEntry {
+                        range: name_range,
+                        diagnostic: Diagnostic {
+                            severity: DiagnosticSeverity::WARNING,
+                            message: "A warning.".to_string(),
+                            group_id: 1,
+                            ..Default::default()
+                        },
+                    },
+                ],
+                &snapshot,
+            );
+            buffer.update_diagnostics(LanguageServerId(0), set, cx);
+        });
+
+        cx.update_editor(|editor, cx| {
+            let point = |column| DisplayPoint::new(DisplayRow(0), column);
+            assert_eq!(editor.diagnostic_message_at(point(0), cx), None);
+            assert_eq!(
+                editor.diagnostic_message_at(point(4), cx),
+                Some((DiagnosticSeverity::ERROR, "An error.".to_string()))
+            );
+            assert_eq!(
+                editor.diagnostic_message_at(point(15), cx),
+                Some((DiagnosticSeverity::ERROR, "An error.".to_string()))
+            );
+        });
+
+        cx.update_buffer(|buffer, cx| {
+            let snapshot = buffer.text_snapshot();
+            let set = DiagnosticSet::from_sorted_entries(
+                vec![DiagnosticEntry {
+                    range: name_range,
+                    diagnostic: Diagnostic {
+                        severity: DiagnosticSeverity::WARNING,
+                        message: "A warning.".to_string(),
+                        ..Default::default()
+                    },
+                }],
+                &snapshot,
+            );
+            buffer.update_diagnostics(LanguageServerId(0), set, cx);
+        });
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.diagnostic_message_at(DisplayPoint::new(DisplayRow(0), 4), cx),
+                Some((DiagnosticSeverity::WARNING, "A warning.".to_string()))
+            );
+            assert_eq!(
+                editor.diagnostic_message_at(DisplayPoint::new(DisplayRow(0), 15), cx),
+                None
+            );
+        });
+    }
+
     #[gpui::test]
     // https://github.com/zed-industries/zed/issues/15498
     async fn test_info_hover_with_hrs(cx: &mut gpui::TestAppContext) {
@@ -346,7 +346,7 @@ pub fn next_subword_end(map: &DisplaySnapshot, point: DisplayPoint) -> DisplayPo
 }
 
 /// Returns a position of the start of the current paragraph, where a paragraph
-/// is defined as a run of non-blank lines.
+/// is defined as a run of non-blank lines. Lines hidden by folds are skipped.
 pub fn start_of_paragraph(
     map: &DisplaySnapshot,
     display_point: DisplayPoint,
@@ -359,6 +359,10 @@ pub fn start_of_paragraph(
 
     let mut found_non_blank_line = false;
     for row in (0..point.row + 1).rev() {
+        // Rows hidden inside a fold belong to the fold's display row.
+        if map.is_line_folded(MultiBufferRow(row)) {
+            continue;
+        }
         let blank = map.buffer_snapshot.is_line_blank(MultiBufferRow(row));
         if found_non_blank_line && blank {
             if count <= 1 {
@@ -375,7 +379,7 @@ pub fn start_of_paragraph(
 }
 
 /// Returns a position of the end of the current paragraph, where a paragraph
-/// is defined as a run of non-blank lines.
+/// is defined as a run of non-blank lines. Lines hidden by folds are skipped.
 pub fn end_of_paragraph(
     map: &DisplaySnapshot,
     display_point: DisplayPoint,
@@ -388,6 +392,10 @@ pub fn end_of_paragraph(
 
     let mut found_non_blank_line = false;
     for row in point.row..=map.buffer_snapshot.max_row().0 {
+        // Rows hidden inside a fold belong to the fold's display row.
+        if map.is_line_folded(MultiBufferRow(row)) {
+            continue;
+        }
         let blank = map.buffer_snapshot.is_line_blank(MultiBufferRow(row));
         if found_non_blank_line && blank {
             if count <= 1 {
@@ -20,6 +20,7 @@ pub use scroll_amount::ScrollAmount;
 use settings::Settings;
 use std::{
     cmp::Ordering,
+    ops::Range,
     time::{Duration, Instant},
 };
 use util::ResultExt;
@@ -176,14 +177,18 @@ impl OngoingScroll {
 
 pub struct ScrollManager {
     pub(crate) vertical_scroll_margin: f32,
+    pub(crate) horizontal_autoscroll_margin: f32,
     anchor: ScrollAnchor,
     ongoing: OngoingScroll,
     autoscroll_request: Option<(Autoscroll, bool)>,
+    autoscroll_batch_depth: usize,
+    batched_autoscroll_request: Option<(Autoscroll, bool)>,
     last_autoscroll: Option<(gpui::Point<f32>, f32, f32, AutoscrollStrategy)>,
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: AxisPair<bool>,
     visible_line_count: Option<f32>,
+    visible_rows: Range<DisplayRow>,
     forbid_vertical_scroll: bool,
 }
 
@@ -191,14 +196,19 @@ impl ScrollManager {
     pub fn new(cx: &mut WindowContext) -> Self {
         ScrollManager {
             vertical_scroll_margin: EditorSettings::get_global(cx).vertical_scroll_margin,
+            horizontal_autoscroll_margin: EditorSettings::get_global(cx)
+                .horizontal_autoscroll_margin,
             anchor: ScrollAnchor::new(),
             ongoing: OngoingScroll::new(),
             autoscroll_request: None,
+            autoscroll_batch_depth: 0,
+            batched_autoscroll_request: None,
             show_scrollbars: true,
             hide_scrollbar_task: None,
             dragging_scrollbar: axis_pair(false, false),
             last_autoscroll: None,
             visible_line_count: None,
+            visible_rows: DisplayRow(0)..DisplayRow(0),
             forbid_vertical_scroll: false,
         }
     }
@@ -399,6 +409,19 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn horizontal_autoscroll_margin(&self) -> usize {
+        self.scroll_manager.horizontal_autoscroll_margin as usize
+    }
+
+    pub fn set_horizontal_autoscroll_margin(
+        &mut self,
+        margin_columns: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.scroll_manager.horizontal_autoscroll_margin = margin_columns as f32;
+        cx.notify();
+    }
+
     pub fn visible_line_count(&self) -> Option<f32> {
         self.scroll_manager.visible_line_count
     }
@@ -408,6 +431,38 @@ impl Editor {
             .map(|line_count| line_count as u32 - 1)
     }
 
+    /// The display rows that were laid out during the most recent render.
+    ///
+    /// The range is empty until the editor has been rendered at least once.
+    pub fn visible_row_range(&self) -> Range<DisplayRow> {
+        self.scroll_manager.visible_rows.clone()
+    }
+
+    /// The buffer range corresponding to [`Editor::visible_row_range`].
+    pub fn visible_anchor_range(&self, cx: &mut ViewContext<Self>) -> Range<Anchor> {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let rows = self.visible_row_range();
+        let start = if rows.start == DisplayRow(0) {
+            Anchor::min()
+        } else {
+            display_map
+                .buffer_snapshot
+                .anchor_before(DisplayPoint::new(rows.start, 0).to_offset(&display_map, Bias::Left))
+        };
+        let end = if rows.end > display_map.max_point().row() {
+            Anchor::max()
+        } else {
+            display_map
+                .buffer_snapshot
+                .anchor_before(DisplayPoint::new(rows.end, 0).to_offset(&display_map, Bias::Right))
+        };
+        start..end
+    }
+
+    pub(crate) fn set_visible_row_range(&mut self, rows: Range<DisplayRow>) {
+        self.scroll_manager.visible_rows = rows;
+    }
+
     pub(crate) fn set_visible_line_count(&mut self, lines: f32, cx: &mut ViewContext<Self>) {
         let opened_first_time = self.scroll_manager.visible_line_count.is_none();
         self.scroll_manager.visible_line_count = Some(lines);
@@ -486,6 +541,15 @@ impl Editor {
         self.scroll_manager.anchor.scroll_position(&display_map)
     }
 
+    /// Returns the current scroll position as an anchor into the buffer plus an offset from it.
+    ///
+    /// Unlike [`Editor::scroll_position`], the anchor tracks the text at the top of the viewport
+    /// across edits, so it can be used to save and later restore the scroll position via
+    /// [`Editor::set_scroll_anchor`].
+    pub fn scroll_anchor(&self) -> ScrollAnchor {
+        self.scroll_manager.anchor()
+    }
+
     pub fn set_scroll_anchor(&mut self, scroll_anchor: ScrollAnchor, cx: &mut ViewContext<Self>) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().and_then(|workspace| workspace.1);
@@ -1,8 +1,10 @@
 use crate::{
-    display_map::ToDisplayPoint, DisplayRow, Editor, EditorMode, LineWithInvisibles, RowExt,
+    display_map::ToDisplayPoint, DisplayRow, Editor, EditorMode, EditorSettings,
+    LineWithInvisibles, RowExt, SoftWrap,
 };
 use gpui::{px, Bounds, Pixels, ViewContext};
 use language::Point;
+use settings::Settings;
 use std::{cmp, f32};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -232,8 +234,19 @@ impl Editor {
         layouts: &[LineWithInvisibles],
         cx: &mut ViewContext<Self>,
     ) -> bool {
+        // Lines wrapped to the editor width never extend past the viewport.
+        if !EditorSettings::get_global(cx).horizontal_autoscroll
+            || matches!(
+                self.soft_wrap_mode(cx),
+                SoftWrap::EditorWidth | SoftWrap::Bounded(_)
+            )
+        {
+            return false;
+        }
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let selections = self.selections.all::<Point>(cx);
+        let margin = self.scroll_manager.horizontal_autoscroll_margin as u32;
 
         let mut target_left;
         let mut target_right;
@@ -249,8 +262,9 @@ impl Editor {
                 if head.row() >= start_row
                     && head.row() < DisplayRow(start_row.0 + layouts.len() as u32)
                 {
-                    let start_column = head.column().saturating_sub(3);
-                    let end_column = cmp::min(display_map.line_len(head.row()), head.column() + 3);
+                    let start_column = head.column().saturating_sub(margin);
+                    let end_column =
+                        cmp::min(display_map.line_len(head.row()), head.column() + margin);
                     target_left = target_left.min(
                         layouts[head.row().minus(start_row) as usize]
                             .x_for_index(start_column as usize),
@@ -288,8 +302,7 @@ impl Editor {
     }
 
     pub fn request_autoscroll(&mut self, autoscroll: Autoscroll, cx: &mut ViewContext<Self>) {
-        self.scroll_manager.autoscroll_request = Some((autoscroll, true));
-        cx.notify();
+        self.set_autoscroll_request((autoscroll, true), cx);
     }
 
     pub(crate) fn request_autoscroll_remotely(
@@ -297,7 +310,41 @@ impl Editor {
         autoscroll: Autoscroll,
         cx: &mut ViewContext<Self>,
     ) {
-        self.scroll_manager.autoscroll_request = Some((autoscroll, false));
-        cx.notify();
+        self.set_autoscroll_request((autoscroll, false), cx);
+    }
+
+    /// Runs `update`, coalescing the autoscroll requests it makes into a
+    /// single request for the last of them, issued once `update` returns.
+    /// Selection changes made inside the batch don't notify or run their side
+    /// effects until the outermost batch ends, when they run once.
+    pub fn batch_autoscroll<R>(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+        update: impl FnOnce(&mut Self, &mut ViewContext<Self>) -> R,
+    ) -> R {
+        self.scroll_manager.autoscroll_batch_depth += 1;
+        let result = update(self, cx);
+        self.scroll_manager.autoscroll_batch_depth -= 1;
+
+        if self.scroll_manager.autoscroll_batch_depth == 0 {
+            if let Some(request) = self.scroll_manager.batched_autoscroll_request.take() {
+                self.set_autoscroll_request(request, cx);
+            }
+            if let Some((old_cursor_position, request_completions)) =
+                self.batched_selection_change.take()
+            {
+                self.local_selections_did_change(&old_cursor_position, request_completions, cx);
+            }
+        }
+        result
+    }
+
+    fn set_autoscroll_request(&mut self, request: (Autoscroll, bool), cx: &mut ViewContext<Self>) {
+        if self.scroll_manager.autoscroll_batch_depth > 0 {
+            self.scroll_manager.batched_autoscroll_request = Some(request);
+        } else {
+            self.scroll_manager.autoscroll_request = Some(request);
+            cx.notify();
+        }
     }
 }
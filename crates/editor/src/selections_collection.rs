@@ -18,6 +18,15 @@ use crate::{
     ToOffset, ToPoint,
 };
 
+/// Whether selections that overlap or touch are merged when they're set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeBehavior {
+    #[default]
+    Merge,
+    /// Keep every selection distinct, even when it overlaps another one.
+    Keep,
+}
+
 #[derive(Debug, Clone)]
 pub struct PendingSelection {
     pub selection: Selection<Anchor>,
@@ -30,7 +39,9 @@ pub struct SelectionsCollection {
     buffer: Model<MultiBuffer>,
     pub next_selection_id: usize,
     pub line_mode: bool,
-    /// The non-pending, non-overlapping selections.
+    pub merge_behavior: MergeBehavior,
+    /// The non-pending, non-overlapping selections, unless `merge_behavior`
+    /// is [`MergeBehavior::Keep`].
     /// The [SelectionsCollection::pending] selection could possibly overlap these
     pub disjoint: Arc<[Selection<Anchor>]>,
     /// A pending selection, such as when the mouse is being dragged
@@ -44,6 +55,7 @@ impl SelectionsCollection {
             buffer,
             next_selection_id: 1,
             line_mode: false,
+            merge_behavior: MergeBehavior::default(),
             disjoint: Arc::default(),
             pending: Some(PendingSelection {
                 selection: Selection {
@@ -69,6 +81,7 @@ impl SelectionsCollection {
     pub fn clone_state(&mut self, other: &SelectionsCollection) {
         self.next_selection_id = other.next_selection_id;
         self.line_mode = other.line_mode;
+        self.merge_behavior = other.merge_behavior;
         self.disjoint = other.disjoint.clone();
         self.pending.clone_from(&other.pending);
     }
@@ -527,10 +540,10 @@ impl<'a> MutableSelectionsCollection<'a> {
         T: ToOffset + ToPoint + Ord + std::marker::Copy + std::fmt::Debug,
     {
         let buffer = self.buffer.read(self.cx).snapshot(self.cx);
-        selections.sort_unstable_by_key(|s| s.start);
+        selections.sort_by_key(|s| s.start);
         // Merge overlapping selections.
         let mut i = 1;
-        while i < selections.len() {
+        while self.merge_behavior == MergeBehavior::Merge && i < selections.len() {
             if selections[i - 1].end >= selections[i].start {
                 let removed = selections.remove(i);
                 if removed.start < selections[i - 1].start {
@@ -103,6 +103,8 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The buffer's text as of `saved_version`, if known.
+    saved_text: Option<Rope>,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -173,6 +175,38 @@ pub enum IndentKind {
     Tab,
 }
 
+/// How the lines of a buffer are indented, as detected by [`BufferSnapshot::indentation_report`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum IndentationStyle {
+    /// No line in the buffer is indented.
+    #[default]
+    None,
+    /// Lines are indented with spaces only.
+    Spaces,
+    /// Lines are indented with tabs only.
+    Tabs,
+    /// Some lines are indented with tabs and others with spaces, or lines
+    /// mix both characters in their leading whitespace.
+    Mixed,
+}
+
+/// A summary of the leading whitespace used throughout a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct IndentationReport {
+    /// The kind of whitespace used for indentation.
+    pub style: IndentationStyle,
+    /// The unit of indentation inferred from the buffer's contents, or `None`
+    /// if no line is indented. For spaces, this is the most common difference
+    /// in indentation between consecutive non-blank lines.
+    pub indent_size: Option<IndentSize>,
+    /// The number of lines indented with spaces only.
+    pub space_indented_lines: u32,
+    /// The number of lines indented with tabs only.
+    pub tab_indented_lines: u32,
+    /// The number of lines whose indentation contains both tabs and spaces.
+    pub mixed_indented_lines: u32,
+}
+
 /// The shape of a selection cursor.
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -561,6 +595,10 @@ pub struct Diff {
     pub edits: Vec<(Range<usize>, Arc<str>)>,
 }
 
+/// The number of rows on either side of a range that
+/// [`BufferSnapshot::text_enclosing_bracket_ranges`] scans for brackets.
+pub(crate) const TEXT_BRACKET_SCAN_ROWS: u32 = 500;
+
 #[derive(Clone, Copy)]
 pub(crate) struct DiagnosticEndpoint {
     offset: usize,
@@ -653,6 +691,13 @@ impl Buffer {
         message: proto::BufferState,
         file: Option<Arc<dyn File>>,
     ) -> Result<Self> {
+        if message.version > proto::BUFFER_STATE_VERSION {
+            return Err(anyhow!(
+                "unsupported buffer state version {}, expected at most {}",
+                message.version,
+                proto::BUFFER_STATE_VERSION
+            ));
+        }
         let buffer_id = BufferId::new(message.id)
             .with_context(|| anyhow!("Could not deserialize buffer_id"))?;
         let buffer = TextBuffer::new(replica_id, buffer_id, message.base_text);
@@ -662,6 +707,7 @@ impl Buffer {
                 .ok_or_else(|| anyhow!("missing line_ending"))?,
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
+        this.saved_text = None;
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
         Ok(this)
     }
@@ -675,6 +721,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            version: proto::BUFFER_STATE_VERSION,
         }
     }
 
@@ -752,6 +799,7 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            saved_text: Some(buffer.as_rope().clone()),
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -974,6 +1022,7 @@ impl Buffer {
         cx: &mut ModelContext<Self>,
     ) {
         self.saved_version = version;
+        self.update_saved_text();
         self.has_unsaved_edits
             .set((self.saved_version().clone(), false));
         self.has_conflict = false;
@@ -1040,6 +1089,7 @@ impl Buffer {
         cx: &mut ModelContext<Self>,
     ) {
         self.saved_version = version;
+        self.update_saved_text();
         self.has_unsaved_edits
             .set((self.saved_version.clone(), false));
         self.text.set_line_ending(line_ending);
@@ -1658,13 +1708,29 @@ impl Buffer {
             return has_unsaved_edits;
         }
 
-        let has_edits = self.has_edits_since(&self.saved_version);
+        let has_edits = self.has_edits_since(&self.saved_version)
+            && self
+                .saved_text
+                .as_ref()
+                .map_or(true, |saved_text| saved_text != self.as_rope());
         self.has_unsaved_edits
             .set((self.version.clone(), has_edits));
         has_edits
     }
 
-    /// Checks if the buffer has unsaved changes.
+    /// Returns a hash of the buffer's visible text, which is the same for any
+    /// two buffers with the same text. The hash is read from the rope's
+    /// summary, so it doesn't scan the text.
+    pub fn content_fingerprint(&self) -> u64 {
+        self.as_rope().fingerprint()
+    }
+
+    fn update_saved_text(&mut self) {
+        self.saved_text = (self.saved_version == self.version).then(|| self.as_rope().clone());
+    }
+
+    /// Checks if the buffer has unsaved changes. Edits that restore the text
+    /// to its saved contents don't count.
     pub fn is_dirty(&self) -> bool {
         self.capability != Capability::ReadOnly
             && (self.has_conflict
@@ -1772,6 +1838,17 @@ impl Buffer {
         self.text.merge_transactions(transaction, destination);
     }
 
+    /// Labels a transaction in the buffer's undo history, e.g. with the name of
+    /// the action that produced it.
+    pub fn set_transaction_description(
+        &mut self,
+        transaction_id: TransactionId,
+        description: impl Into<Arc<str>>,
+    ) {
+        self.text
+            .set_transaction_description(transaction_id, description);
+    }
+
     /// Waits for the buffer to receive operations with the given timestamps.
     pub fn wait_for_edits(
         &mut self,
@@ -2487,6 +2564,57 @@ impl BufferSnapshot {
     pub fn indent_size_for_line(&self, row: u32) -> IndentSize {
         indent_size_for_line(self, row)
     }
+
+    /// Scans the leading whitespace of every non-blank line, reporting whether
+    /// the buffer is indented with tabs, spaces, or a mix of both, along with
+    /// the indent size it appears to use.
+    pub fn indentation_report(&self) -> IndentationReport {
+        let mut report = IndentationReport::default();
+        let mut space_indent_deltas = HashMap::<u32, usize>::default();
+        let mut previous_space_indent = 0;
+        for row in 0..=self.max_point().row {
+            let indent = self.line_indent_for_row(row);
+            if indent.line_blank {
+                continue;
+            }
+
+            match (indent.tabs, indent.spaces) {
+                (0, 0) => previous_space_indent = 0,
+                (0, spaces) => {
+                    report.space_indented_lines += 1;
+                    let delta = spaces.abs_diff(previous_space_indent);
+                    if delta > 0 {
+                        *space_indent_deltas.entry(delta).or_default() += 1;
+                    }
+                    previous_space_indent = spaces;
+                }
+                (_, 0) => report.tab_indented_lines += 1,
+                _ => report.mixed_indented_lines += 1,
+            }
+        }
+
+        let space_indent_size = space_indent_deltas
+            .into_iter()
+            .max_by_key(|(delta, count)| (*count, Reverse(*delta)))
+            .map(|(delta, _)| IndentSize::spaces(delta));
+        let uses_spaces = report.space_indented_lines > 0;
+        let uses_tabs = report.tab_indented_lines > 0;
+        if report.mixed_indented_lines > 0 || (uses_spaces && uses_tabs) {
+            report.style = IndentationStyle::Mixed;
+            report.indent_size = if report.space_indented_lines > report.tab_indented_lines {
+                space_indent_size
+            } else {
+                Some(IndentSize::tab())
+            };
+        } else if uses_spaces {
+            report.style = IndentationStyle::Spaces;
+            report.indent_size = space_indent_size;
+        } else if uses_tabs {
+            report.style = IndentationStyle::Tabs;
+            report.indent_size = Some(IndentSize::tab());
+        }
+        report
+    }
     /// Returns [`IndentSize`] for a given position that respects user settings
     /// and language preferences.
     pub fn language_indent_size_at<T: ToOffset>(&self, position: T, cx: &AppContext) -> IndentSize {
@@ -2923,6 +3051,48 @@ impl BufferSnapshot {
         (start..end, word_kind)
     }
 
+    /// Returns the range of the word or punctuation run under the given
+    /// position, or an empty range at that position if it is surrounded
+    /// by whitespace.
+    pub fn word_range_at<T: ToOffset>(&self, position: T) -> Range<usize> {
+        let offset = position.to_offset(self);
+        match self.surrounding_word(offset) {
+            (range, Some(CharKind::Word | CharKind::Punctuation)) => range,
+            _ => offset..offset,
+        }
+    }
+
+    /// Returns the words in the given range along with their text. Words that
+    /// straddle the range's boundaries are truncated to the range.
+    ///
+    /// Since this only borrows the snapshot, it can be used from a background
+    /// task without holding onto the buffer.
+    pub fn words_in_range<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, String)> + '_ {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let classifier = self.char_classifier_at(range.start);
+        let mut chars = self
+            .text_for_range(range.clone())
+            .flat_map(str::chars)
+            .peekable();
+        let mut offset = range.start;
+        iter::from_fn(move || {
+            while !classifier.is_word(*chars.peek()?) {
+                offset += chars.next()?.len_utf8();
+            }
+
+            let start = offset;
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| classifier.is_word(*c)) {
+                offset += c.len_utf8();
+                word.push(c);
+            }
+            Some((start..offset, word))
+        })
+    }
+
     /// Returns the closest syntax node enclosing the given range.
     pub fn syntax_ancestor<'a, T: ToOffset>(
         &'a self,
@@ -3471,6 +3641,94 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns enclosing bracket ranges containing the given range, found by matching bracket
+    /// pairs in the text rather than in the syntax tree. This is used for buffers without a
+    /// grammar, and matches the language's bracket pairs or, failing those, `()`, `[]` and `{}`.
+    /// Only the [`TEXT_BRACKET_SCAN_ROWS`] rows on either side of the range are scanned.
+    pub fn text_enclosing_bracket_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Vec<(Range<usize>, Range<usize>)> {
+        const DEFAULT_PAIRS: [(&str, &str); 3] = [("(", ")"), ("[", "]"), ("{", "}")];
+
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        // Pairs with identical delimiters, such as quotes, can't be matched without a grammar.
+        let language_pairs = self.language.as_ref().map_or(Vec::new(), |language| {
+            language
+                .config
+                .brackets
+                .pairs
+                .iter()
+                .filter(|pair| !pair.start.is_empty() && !pair.end.is_empty())
+                .filter(|pair| pair.start != pair.end)
+                .map(|pair| (pair.start.as_str(), pair.end.as_str()))
+                .collect()
+        });
+        let pairs = if language_pairs.is_empty() {
+            &DEFAULT_PAIRS[..]
+        } else {
+            &language_pairs[..]
+        };
+
+        let start_row = self.offset_to_point(range.start).row;
+        let end_row = self.offset_to_point(range.end).row;
+        let scan_start = self.point_to_offset(Point::new(
+            start_row.saturating_sub(TEXT_BRACKET_SCAN_ROWS),
+            0,
+        ));
+        let scan_end_row = cmp::min(
+            end_row.saturating_add(TEXT_BRACKET_SCAN_ROWS),
+            self.max_point().row,
+        );
+        let scan_end = self.point_to_offset(Point::new(scan_end_row, self.line_len(scan_end_row)));
+
+        let mut result = Vec::new();
+        let mut open_brackets = Vec::<(usize, Range<usize>)>::new();
+        let mut offset = scan_start;
+        let mut next_offset = scan_start;
+        for ch in self.chars_at(scan_start) {
+            let ch_offset = offset;
+            offset += ch.len_utf8();
+            if ch_offset < next_offset {
+                continue;
+            }
+
+            // Stop once no open bracket before the range can still be closed after it.
+            if ch_offset >= scan_end
+                || (ch_offset >= range.end
+                    && open_brackets
+                        .first()
+                        .map_or(true, |(_, open)| open.start > range.start))
+            {
+                break;
+            }
+
+            if let Some(pair_ix) = pairs.iter().position(|(start, _)| {
+                start.starts_with(ch) && self.contains_str_at(ch_offset, start)
+            }) {
+                next_offset = ch_offset + pairs[pair_ix].0.len();
+                open_brackets.push((pair_ix, ch_offset..next_offset));
+            } else if let Some(pair_ix) = pairs
+                .iter()
+                .position(|(_, end)| end.starts_with(ch) && self.contains_str_at(ch_offset, end))
+            {
+                next_offset = ch_offset + pairs[pair_ix].1.len();
+                if open_brackets
+                    .last()
+                    .map_or(false, |(open_ix, _)| *open_ix == pair_ix)
+                {
+                    let (_, open) = open_brackets.pop().unwrap();
+                    let close = ch_offset..next_offset;
+                    if open.start <= range.start && close.end >= range.end {
+                        result.push((open, close));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     /// Returns anchor ranges for any matches of the redaction query.
     /// The buffer can be associated with multiple languages, and the redaction query associated with each
     /// will be run on the relevant section of the buffer.
@@ -3890,6 +4148,9 @@ impl BufferSnapshot {
     }
 
     /// Returns selections for remote peers intersecting the given range.
+    ///
+    /// Selection sets are yielded in order of replica id, and the selections within
+    /// each set are ordered by their position in the buffer.
     #[allow(clippy::type_complexity)]
     pub fn selections_in_range(
         &self,
@@ -3930,6 +4191,23 @@ impl BufferSnapshot {
             })
     }
 
+    /// Returns the ids of the replicas with non-empty selections in this buffer, ordered
+    /// from the least to the most recently updated. Rendering selections in this order
+    /// draws the newest ones on top.
+    pub fn selection_sets_by_recency(&self, include_local: bool) -> Vec<ReplicaId> {
+        let mut sets = self
+            .remote_selections
+            .iter()
+            .filter(|(replica_id, set)| {
+                (include_local || **replica_id != self.text.replica_id())
+                    && !set.selections.is_empty()
+            })
+            .map(|(replica_id, set)| (set.lamport_timestamp, *replica_id))
+            .collect::<Vec<_>>();
+        sets.sort_unstable();
+        sets.into_iter().map(|(_, replica_id)| replica_id).collect()
+    }
+
     /// Returns if the buffer contains any diagnostics.
     pub fn has_diagnostics(&self) -> bool {
         !self.diagnostics.is_empty()
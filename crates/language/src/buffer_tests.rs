@@ -948,6 +948,31 @@ fn test_text_objects(cx: &mut AppContext) {
     )
 }
 
+#[gpui::test]
+fn test_text_enclosing_bracket_ranges(cx: &mut AppContext) {
+    let bracket_ranges = |text: &str, cx: &mut AppContext| {
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx));
+        let snapshot = buffer.read(cx).snapshot();
+        let cursor = text.find('b').unwrap();
+        snapshot
+            .text_enclosing_bracket_ranges(cursor..cursor)
+            .into_iter()
+            .map(|(open, close)| open.start..close.end)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(bracket_ranges("(\n[a b]\n)", cx), vec![2..7, 0..9]);
+
+    // An unmatched opener earlier in the file doesn't enclose the range.
+    assert_eq!(bracket_ranges("(\nx\n[a b]\ny\n", cx), vec![4..9]);
+
+    // Brackets more than `TEXT_BRACKET_SCAN_ROWS` rows away from the range aren't scanned.
+    let gap = "\n".repeat(crate::buffer::TEXT_BRACKET_SCAN_ROWS as usize + 1);
+    let text = format!("({gap}[a b]{gap})");
+    let open = gap.len() + 1;
+    assert_eq!(bracket_ranges(&text, cx), vec![open..open + 5]);
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut AppContext) {
     let mut assert = |selection_text, range_markers| {
@@ -2052,6 +2077,98 @@ async fn test_async_autoindents_preserve_preview(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_indentation_report(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    let report = |text: &str, cx: &mut AppContext| {
+        cx.new_model(|cx| Buffer::local(text, cx))
+            .read(cx)
+            .snapshot()
+            .indentation_report()
+    };
+
+    // Two-space indentation
+    let text = indoc! {r#"
+        {
+          "a": {
+            "b": 1
+          },
+
+          "c": 2
+        }
+    "#};
+    assert_eq!(
+        report(text, cx),
+        IndentationReport {
+            style: IndentationStyle::Spaces,
+            indent_size: Some(IndentSize::spaces(2)),
+            space_indented_lines: 4,
+            tab_indented_lines: 0,
+            mixed_indented_lines: 0,
+        }
+    );
+
+    // Four-space indentation, with an aligned continuation line
+    let text = indoc! {"
+        fn a() {
+            let x = foo(1,
+                        2);
+            if x {
+                b();
+            }
+        }
+    "};
+    assert_eq!(
+        report(text, cx),
+        IndentationReport {
+            style: IndentationStyle::Spaces,
+            indent_size: Some(IndentSize::spaces(4)),
+            space_indented_lines: 5,
+            tab_indented_lines: 0,
+            mixed_indented_lines: 0,
+        }
+    );
+
+    // Tab indentation
+    let text = "fn a() {\n\tif b {\n\t\tc();\n\t}\n}\n";
+    assert_eq!(
+        report(text, cx),
+        IndentationReport {
+            style: IndentationStyle::Tabs,
+            indent_size: Some(IndentSize::tab()),
+            space_indented_lines: 0,
+            tab_indented_lines: 3,
+            mixed_indented_lines: 0,
+        }
+    );
+
+    // Lines indented with tabs, lines indented with spaces, and a line mixing both
+    let text = "fn a() {\n\tb();\n    c();\n    d();\n\t  e();\n}\n";
+    assert_eq!(
+        report(text, cx),
+        IndentationReport {
+            style: IndentationStyle::Mixed,
+            indent_size: Some(IndentSize::spaces(4)),
+            space_indented_lines: 2,
+            tab_indented_lines: 1,
+            mixed_indented_lines: 1,
+        }
+    );
+
+    // No indentation
+    assert_eq!(
+        report("a\n\n  \nb", cx),
+        IndentationReport {
+            style: IndentationStyle::None,
+            indent_size: None,
+            space_indented_lines: 0,
+            tab_indented_lines: 0,
+            mixed_indented_lines: 0,
+        }
+    );
+}
+
 #[gpui::test]
 fn test_insert_empty_line(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -2436,6 +2553,75 @@ fn test_language_at_with_hidden_languages(cx: &mut AppContext) {
     });
 }
 
+#[gpui::test]
+fn test_word_range_at(cx: &mut AppContext) {
+    let buffer = cx.new_model(|cx| Buffer::local("let café = a::b;   x", cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    assert_eq!(snapshot.word_range_at(0), 0..3);
+    assert_eq!(snapshot.word_range_at(6), 4..9);
+    assert_eq!(snapshot.word_range_at(9), 4..9);
+    assert_eq!(snapshot.word_range_at(14), 13..15);
+    assert_eq!(snapshot.word_range_at(18), 18..18);
+    assert_eq!(snapshot.word_range_at(21), 20..21);
+}
+
+#[gpui::test]
+fn test_words_in_range(cx: &mut AppContext) {
+    let buffer = cx.new_model(|cx| Buffer::local("the quikc brwn fox, café", cx));
+    let snapshot = buffer.read(cx).snapshot();
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..3, "a")], None, cx));
+
+    // The snapshot still reflects the text from before the edit.
+    assert_eq!(
+        snapshot
+            .words_in_range(0..snapshot.len())
+            .collect::<Vec<_>>(),
+        [
+            (0..3, "the".to_string()),
+            (4..9, "quikc".to_string()),
+            (10..14, "brwn".to_string()),
+            (15..18, "fox".to_string()),
+            (20..25, "café".to_string()),
+        ]
+    );
+    assert_eq!(
+        snapshot.words_in_range(6..16).collect::<Vec<_>>(),
+        [
+            (6..9, "ikc".to_string()),
+            (10..14, "brwn".to_string()),
+            (15..16, "f".to_string()),
+        ]
+    );
+    assert_eq!(snapshot.words_in_range(18..20).count(), 0);
+}
+
+#[gpui::test]
+fn test_content_fingerprint(cx: &mut AppContext) {
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local("abc", cx);
+        let saved_fingerprint = buffer.content_fingerprint();
+
+        buffer.edit([(1..2, "B")], None, cx);
+        assert_ne!(buffer.content_fingerprint(), saved_fingerprint);
+        assert!(buffer.is_dirty());
+
+        // Retyping the saved text makes the buffer clean again.
+        buffer.edit([(1..2, "b")], None, cx);
+        assert_eq!(buffer.content_fingerprint(), saved_fingerprint);
+        assert!(!buffer.is_dirty());
+
+        // Edits that keep the length but change the text leave the buffer dirty.
+        buffer.edit([(0..3, "cba")], None, cx);
+        assert!(buffer.is_dirty());
+        buffer.edit([(0..3, "abd")], None, cx);
+        assert!(buffer.is_dirty());
+        buffer.edit([(2..3, "c")], None, cx);
+        assert!(!buffer.is_dirty());
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::AppContext) {
     let mut now = Instant::now();
@@ -2475,6 +2661,147 @@ fn test_serialization(cx: &mut gpui::AppContext) {
     assert_eq!(buffer2.read(cx).text(), "abcDF");
 }
 
+#[gpui::test]
+fn test_serialization_version(cx: &mut gpui::AppContext) {
+    let buffer = cx.new_model(|cx| Buffer::local("abc", cx));
+    let state = buffer.read(cx).to_proto(cx);
+    assert_eq!(state.version, proto::BUFFER_STATE_VERSION);
+
+    let replica = Buffer::from_proto(1, Capability::ReadWrite, state.clone(), None).unwrap();
+    assert_eq!(replica.text(), "abc");
+
+    // States from peers that predate versioning are still accepted.
+    let legacy_state = proto::BufferState {
+        version: 0,
+        ..state.clone()
+    };
+    assert!(Buffer::from_proto(1, Capability::ReadWrite, legacy_state, None).is_ok());
+
+    // States from newer peers are rejected rather than misread.
+    let newer_state = proto::BufferState {
+        version: proto::BUFFER_STATE_VERSION + 1,
+        ..state
+    };
+    let error = Buffer::from_proto(1, Capability::ReadWrite, newer_state, None)
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .contains("unsupported buffer state version"));
+}
+
+#[gpui::test]
+fn test_selection_sets_by_recency(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    let selections: Arc<[Selection<Anchor>]> = Arc::from([Selection {
+        id: 0,
+        start: Anchor::MIN,
+        end: Anchor::MIN,
+        reversed: false,
+        goal: SelectionGoal::None,
+    }]);
+    fn update_selections(
+        replica_id: ReplicaId,
+        value: u32,
+        selections: &Arc<[Selection<Anchor>]>,
+    ) -> Operation {
+        Operation::UpdateSelections {
+            selections: selections.clone(),
+            lamport_timestamp: clock::Lamport { replica_id, value },
+            line_mode: false,
+            cursor_shape: CursorShape::default(),
+        }
+    }
+
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local("abc", cx);
+        buffer.apply_ops(
+            [
+                update_selections(3, 1, &selections),
+                update_selections(1, 3, &selections),
+                update_selections(2, 2, &selections),
+            ],
+            cx,
+        );
+        assert_eq!(
+            buffer.snapshot().selection_sets_by_recency(false),
+            [3, 2, 1]
+        );
+
+        // The local selections are the most recent ones after being updated.
+        buffer.set_active_selections(selections.clone(), false, CursorShape::default(), cx);
+        assert_eq!(
+            buffer.snapshot().selection_sets_by_recency(true),
+            [3, 2, 1, 0]
+        );
+        assert_eq!(
+            buffer.snapshot().selection_sets_by_recency(false),
+            [3, 2, 1]
+        );
+
+        // Updating a replica's selections moves it to the end, while replicas that
+        // cleared their selections are omitted.
+        buffer.apply_ops(
+            [
+                update_selections(3, 10, &selections),
+                update_selections(2, 11, &Arc::from([])),
+            ],
+            cx,
+        );
+        assert_eq!(buffer.snapshot().selection_sets_by_recency(true), [1, 0, 3]);
+        buffer
+    });
+}
+
+#[gpui::test]
+fn test_selections_in_range_order(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local("abcdefghij", cx);
+        let selections = |offsets: &[usize]| -> Arc<[Selection<Anchor>]> {
+            offsets
+                .iter()
+                .enumerate()
+                .map(|(id, offset)| Selection {
+                    id,
+                    start: buffer.anchor_before(*offset),
+                    end: buffer.anchor_before(*offset),
+                    reversed: false,
+                    goal: SelectionGoal::None,
+                })
+                .collect()
+        };
+        let operations = [(3, 1, [1, 5]), (1, 2, [0, 9]), (2, 3, [2, 4])]
+            .into_iter()
+            .map(|(replica_id, value, offsets)| Operation::UpdateSelections {
+                selections: selections(&offsets),
+                lamport_timestamp: clock::Lamport { replica_id, value },
+                line_mode: false,
+                cursor_shape: CursorShape::default(),
+            })
+            .collect::<Vec<_>>();
+        buffer.apply_ops(operations, cx);
+
+        // Sets are ordered by replica id regardless of the order they arrived in.
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            snapshot
+                .selections_in_range(Anchor::MIN..Anchor::MAX, false)
+                .map(|(replica_id, _, _, selections)| {
+                    let offsets = selections
+                        .map(|selection| selection.head().to_offset(&snapshot))
+                        .collect::<Vec<_>>();
+                    (replica_id, offsets)
+                })
+                .collect::<Vec<_>>(),
+            [(1, vec![0, 9]), (2, vec![2, 4]), (3, vec![1, 5])]
+        );
+        buffer
+    });
+}
+
 #[gpui::test]
 async fn test_find_matching_indent(cx: &mut TestAppContext) {
     cx.update(|cx| init_settings(cx, |_| {}));
@@ -11,6 +11,13 @@ use text::*;
 
 pub use proto::{BufferState, Operation};
 
+/// The version of [`proto::BufferState`] written by this build. It must be bumped
+/// whenever the buffer state changes in a way older replicas can't decode, and any
+/// fields added along with it should only be read from states that have it.
+///
+/// States with a version of 0 were sent before the field existed.
+pub const BUFFER_STATE_VERSION: u32 = 1;
+
 /// Deserializes a `[text::LineEnding]` from the RPC representation.
 pub fn deserialize_line_ending(message: proto::LineEnding) -> text::LineEnding {
     match message {
@@ -495,6 +502,7 @@ pub fn serialize_transaction(transaction: &Transaction) -> proto::Transaction {
             .map(serialize_timestamp)
             .collect(),
         start: serialize_version(&transaction.start),
+        description: transaction.description.as_deref().map(ToString::to_string),
     }
 }
 
@@ -512,6 +520,7 @@ pub fn deserialize_transaction(transaction: proto::Transaction) -> Result<Transa
             .map(deserialize_timestamp)
             .collect(),
         start: deserialize_version(&transaction.start),
+        description: transaction.description.map(Into::into),
     })
 }
 
@@ -150,6 +150,7 @@ struct Transaction {
     first_edit_at: Instant,
     last_edit_at: Instant,
     suppress_grouping: bool,
+    description: Option<Arc<str>>,
 }
 
 pub trait ToOffset: 'static + fmt::Debug {
@@ -1010,6 +1011,18 @@ impl MultiBuffer {
         }
     }
 
+    /// Sets the maximum time between transactions for them to be undone together,
+    /// both in this multi-buffer and in each of its buffers.
+    pub fn set_group_interval(&mut self, group_interval: Duration, cx: &mut ModelContext<Self>) {
+        if group_interval != self.history.group_interval {
+            self.history.finalize_last_transaction();
+            self.history.group_interval = group_interval;
+        }
+        for BufferState { buffer, .. } in self.buffers.borrow().values() {
+            buffer.update(cx, |buffer, _| buffer.set_group_interval(group_interval));
+        }
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut ModelContext<Self>) {
         self.history.finalize_last_transaction();
         for BufferState { buffer, .. } in self.buffers.borrow().values() {
@@ -1042,6 +1055,34 @@ impl MultiBuffer {
         }
     }
 
+    pub fn set_transaction_description(
+        &mut self,
+        transaction_id: TransactionId,
+        description: impl Into<Arc<str>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(buffer) = self.as_singleton() {
+            buffer.update(cx, |buffer, _| {
+                buffer.set_transaction_description(transaction_id, description)
+            });
+        } else if let Some(transaction) = self.history.transaction_mut(transaction_id) {
+            transaction.description = Some(description.into());
+        }
+    }
+
+    /// Returns the description of the transaction that would be reverted by the next undo.
+    pub fn undo_description(&self, cx: &AppContext) -> Option<Arc<str>> {
+        if let Some(buffer) = self.as_singleton() {
+            buffer
+                .read(cx)
+                .peek_undo_stack()?
+                .description()
+                .map(Arc::from)
+        } else {
+            self.history.undo_stack.last()?.description.clone()
+        }
+    }
+
     pub fn set_active_selections(
         &self,
         selections: &[Selection<Anchor>],
@@ -1127,6 +1168,46 @@ impl MultiBuffer {
         }
     }
 
+    /// Undoes every transaction made after `checkpoint`, leaving it at the top
+    /// of the undo stack. Returns the undone transactions, most recent first.
+    pub fn undo_to_checkpoint(
+        &mut self,
+        checkpoint: TransactionId,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<TransactionId> {
+        let mut undone = Vec::new();
+        if !self.undo_stack_contains(checkpoint, cx) {
+            return undone;
+        }
+
+        while self.peek_undo_transaction_id(cx) != Some(checkpoint) {
+            match self.undo(cx) {
+                Some(transaction_id) => undone.push(transaction_id),
+                None => break,
+            }
+        }
+        undone
+    }
+
+    fn undo_stack_contains(&self, transaction_id: TransactionId, cx: &AppContext) -> bool {
+        if let Some(buffer) = self.as_singleton() {
+            buffer.read(cx).undo_stack_contains(transaction_id)
+        } else {
+            self.history
+                .undo_stack
+                .iter()
+                .any(|transaction| transaction.id == transaction_id)
+        }
+    }
+
+    fn peek_undo_transaction_id(&self, cx: &AppContext) -> Option<TransactionId> {
+        if let Some(buffer) = self.as_singleton() {
+            Some(buffer.read(cx).peek_undo_stack()?.transaction_id())
+        } else {
+            Some(self.history.undo_stack.last()?.id)
+        }
+    }
+
     pub fn undo(&mut self, cx: &mut ModelContext<Self>) -> Option<TransactionId> {
         let mut transaction_id = None;
         if let Some(buffer) = self.as_singleton() {
@@ -3669,6 +3750,34 @@ impl MultiBufferSnapshot {
         )
     }
 
+    /// Returns enclosing bracket ranges containing the given range, found by matching bracket
+    /// pairs in the text, or returns None if the range is not contained in a single excerpt
+    pub fn text_enclosing_bracket_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Option<Vec<(Range<usize>, Range<usize>)>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let excerpt = self.excerpt_containing(range.clone())?;
+
+        Some(
+            excerpt
+                .buffer()
+                .text_enclosing_bracket_ranges(excerpt.map_range_to_buffer(range))
+                .into_iter()
+                .filter_map(|(open, close)| {
+                    if excerpt.contains_buffer_range(open.start..close.end) {
+                        Some((
+                            excerpt.map_range_from_buffer(open),
+                            excerpt.map_range_from_buffer(close),
+                        ))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
+
     /// Returns bracket range pairs overlapping the given `range` or returns None if the `range` is
     /// not contained in a single excerpt
     pub fn bracket_ranges<T: ToOffset>(
@@ -4318,6 +4427,7 @@ impl History {
                 first_edit_at: now,
                 last_edit_at: now,
                 suppress_grouping: false,
+                description: None,
             });
             Some(id)
         } else {
@@ -4371,6 +4481,7 @@ impl History {
             first_edit_at: now,
             last_edit_at: now,
             suppress_grouping: false,
+            description: None,
         };
         if !transaction.buffer_transactions.is_empty() {
             self.undo_stack.push(transaction);
@@ -4501,6 +4612,9 @@ impl History {
                         .entry(*buffer_id)
                         .or_insert(*transaction_id);
                 }
+                if last_transaction.description.is_none() {
+                    last_transaction.description = to_merge.description.clone();
+                }
             }
         }
 
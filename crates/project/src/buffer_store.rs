@@ -1,5 +1,6 @@
 use crate::{
     lsp_store::OpenLspBufferHandle,
+    project_settings::{LineEndingDetection, ProjectSettings},
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     ProjectItem as _, ProjectPath,
@@ -25,6 +26,7 @@ use language::{
 };
 use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
 use serde::Deserialize;
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{
     io,
@@ -795,13 +797,34 @@ impl LocalBufferStore {
     ) -> Task<Result<Model<Buffer>>> {
         let load_buffer = worktree.update(cx, |worktree, cx| {
             let load_file = worktree.load_file(path.as_ref(), cx);
+            let line_ending_detection = ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.id(),
+                    path: path.as_ref(),
+                }),
+                cx,
+            )
+            .line_ending_detection;
             let reservation = cx.reserve_model();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(move |_, mut cx| async move {
                 let loaded = load_file.await?;
                 let text_buffer = cx
                     .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                    .spawn(async move {
+                        match line_ending_detection {
+                            LineEndingDetection::FirstLine => {
+                                text::Buffer::new(0, buffer_id, loaded.text)
+                            }
+                            LineEndingDetection::Dominant => {
+                                text::Buffer::new_with_dominant_line_ending(
+                                    0,
+                                    buffer_id,
+                                    loaded.text,
+                                )
+                            }
+                        }
+                    })
                     .await;
                 cx.insert_model(reservation, |_| {
                     Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
@@ -56,6 +56,22 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// How to choose the line ending of a file that mixes line endings.
+    ///
+    /// Default: first_line
+    #[serde(default)]
+    pub line_ending_detection: LineEndingDetection,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEndingDetection {
+    /// Use the line ending of the file's first line.
+    #[default]
+    FirstLine,
+    /// Use whichever line ending is most common in the file.
+    Dominant,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -224,6 +224,16 @@ impl Rope {
         self.chunks.summary().text.clone()
     }
 
+    /// Returns a hash of the rope's text. Ropes with the same text have the
+    /// same fingerprint, regardless of how their text is split into chunks.
+    ///
+    /// The hash is maintained in the chunk summaries, so this doesn't scan the
+    /// text. Different texts can share a fingerprint, so use it to rule out
+    /// equality rather than to establish it.
+    pub fn fingerprint(&self) -> u64 {
+        self.chunks.summary().fingerprint.hash
+    }
+
     pub fn len(&self) -> usize {
         self.chunks.extent(&())
     }
@@ -934,12 +944,59 @@ impl<'a> Lines<'a> {
     }
 }
 
+impl PartialEq for Rope {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() || self.fingerprint() != other.fingerprint() {
+            return false;
+        }
+
+        let mut chunks = self.chunks();
+        let mut other_chunks = other.chunks();
+        let mut chunk: &[u8] = &[];
+        let mut other_chunk: &[u8] = &[];
+        loop {
+            if chunk.is_empty() {
+                match chunks.next() {
+                    Some(next) => chunk = next.as_bytes(),
+                    None => return other_chunk.is_empty() && other_chunks.next().is_none(),
+                }
+                continue;
+            }
+            if other_chunk.is_empty() {
+                match other_chunks.next() {
+                    Some(next) => other_chunk = next.as_bytes(),
+                    None => return false,
+                }
+                continue;
+            }
+
+            let len = cmp::min(chunk.len(), other_chunk.len());
+            if chunk[..len] != other_chunk[..len] {
+                return false;
+            }
+            chunk = &chunk[len..];
+            other_chunk = &other_chunk[len..];
+        }
+    }
+}
+
+impl Eq for Rope {}
+
+#[cfg(test)]
+thread_local! {
+    static CHUNK_SUMMARY_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 impl sum_tree::Item for Chunk {
     type Summary = ChunkSummary;
 
     fn summary(&self, _cx: &()) -> Self::Summary {
+        #[cfg(test)]
+        CHUNK_SUMMARY_COUNT.with(|count| count.set(count.get() + 1));
+
         ChunkSummary {
             text: self.as_slice().text_summary(),
+            fingerprint: Fingerprint::from(self.text.as_str()),
         }
     }
 }
@@ -947,6 +1004,54 @@ impl sum_tree::Item for Chunk {
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ChunkSummary {
     text: TextSummary,
+    fingerprint: Fingerprint,
+}
+
+/// A polynomial hash of a string's bytes. The fingerprint of two concatenated
+/// strings can be computed from the fingerprints of each string, so ropes can
+/// combine it from their chunk summaries.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Fingerprint {
+    hash: u64,
+    /// `BASE` raised to the number of bytes hashed.
+    multiplier: u64,
+}
+
+impl Fingerprint {
+    const BASE: u64 = 0x100000001b3;
+}
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Self {
+            hash: 0,
+            multiplier: 1,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Fingerprint {
+    fn from(text: &'a str) -> Self {
+        let mut fingerprint = Self::default();
+        for byte in text.bytes() {
+            fingerprint.hash = fingerprint
+                .hash
+                .wrapping_mul(Self::BASE)
+                .wrapping_add(byte as u64);
+            fingerprint.multiplier = fingerprint.multiplier.wrapping_mul(Self::BASE);
+        }
+        fingerprint
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for Fingerprint {
+    fn add_assign(&mut self, other: &'a Self) {
+        self.hash = self
+            .hash
+            .wrapping_mul(other.multiplier)
+            .wrapping_add(other.hash);
+        self.multiplier = self.multiplier.wrapping_mul(other.multiplier);
+    }
 }
 
 impl sum_tree::Summary for ChunkSummary {
@@ -958,6 +1063,7 @@ impl sum_tree::Summary for ChunkSummary {
 
     fn add_summary(&mut self, summary: &Self, _: &()) {
         self.text += &summary.text;
+        self.fingerprint += &summary.fingerprint;
     }
 }
 
@@ -1255,6 +1361,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fingerprint() {
+        let text = "one\ntwo 🏀\nthree\n".repeat(64);
+        let mut chunked = Rope::new();
+        for piece in text.split_inclusive(' ') {
+            chunked.push(piece);
+        }
+        assert_eq!(
+            chunked.fingerprint(),
+            Rope::from(text.as_str()).fingerprint()
+        );
+
+        let mut edited = chunked.clone();
+        edited.replace(4..7, "TWO");
+        assert_ne!(edited.fingerprint(), chunked.fingerprint());
+        edited.replace(4..7, "two");
+        assert_eq!(edited.fingerprint(), chunked.fingerprint());
+        assert!(edited == chunked);
+    }
+
+    #[test]
+    fn test_fingerprint_is_incremental() {
+        let mut rope = Rope::from("one\ntwo\nthree\n".repeat(1024).as_str());
+        let chunk_count = rope.chunks.iter().count();
+
+        CHUNK_SUMMARY_COUNT.with(|count| count.set(0));
+        rope.replace(4..7, "TWO");
+        let fingerprint = rope.fingerprint();
+        let summarized_chunks = CHUNK_SUMMARY_COUNT.with(|count| count.get());
+        assert!(
+            summarized_chunks < 16,
+            "summarized {summarized_chunks} of {chunk_count} chunks"
+        );
+        assert_eq!(fingerprint, Rope::from(rope.text().as_str()).fingerprint());
+    }
+
+    #[test]
+    fn test_eq() {
+        let text = "one\ntwo 🏀\nthree\n".repeat(8);
+        let mut chunked = Rope::new();
+        for piece in text.split_inclusive(' ') {
+            chunked.push(piece);
+        }
+        assert!(chunked == Rope::from(text.as_str()));
+
+        // Same length, different text.
+        let mut edited = chunked.clone();
+        edited.replace(4..7, "owt");
+        assert!(edited != chunked);
+
+        // Different length.
+        edited.replace(4..7, "two!");
+        assert!(edited != chunked);
+    }
+
     #[test]
     fn test_all_4_byte_chars() {
         let mut rope = Rope::new();
@@ -164,6 +164,82 @@ fn test_random_edits(mut rng: StdRng) {
     }
 }
 
+#[test]
+fn test_set_text() {
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        "fn main() {\n    println!(\"hi\");\n}\n".into(),
+    );
+    let prefix_anchor = buffer.anchor_before(3);
+    let suffix_anchor = buffer.anchor_before(29);
+    let subscription = buffer.subscribe();
+
+    buffer.set_text("fn main() {\n    println!(\"hello\");\n}\n");
+    assert_eq!(buffer.text(), "fn main() {\n    println!(\"hello\");\n}\n");
+    assert_eq!(prefix_anchor.to_offset(&buffer), 3);
+    assert_eq!(suffix_anchor.to_offset(&buffer), 32);
+    assert_eq!(
+        subscription.consume().into_inner(),
+        [Edit {
+            old: 27..28,
+            new: 27..31,
+        }]
+    );
+
+    // The common prefix and suffix never overlap.
+    buffer.set_text("aaaa");
+    buffer.set_text("aa");
+    assert_eq!(buffer.text(), "aa");
+    buffer.set_text("aaaaaa");
+    assert_eq!(buffer.text(), "aaaaaa");
+    buffer.set_text("");
+    assert_eq!(buffer.text(), "");
+}
+
+#[test]
+fn test_edit_returning_ranges() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abcdef".into());
+    let (_, ranges) = buffer.edit_returning_ranges([(1..2, "XY"), (4..4, "Z"), (5..6, "")]);
+    assert_eq!(buffer.text(), "aXYcdZe");
+    assert_eq!(
+        ranges
+            .iter()
+            .map(|range| range.start.to_offset(&buffer)..range.end.to_offset(&buffer))
+            .collect::<Vec<_>>(),
+        [1..3, 5..6, 7..7]
+    );
+
+    // Text inserted at the boundaries of a range stays outside of it.
+    buffer.edit([(1..1, "<"), (3..3, ">")]);
+    assert_eq!(buffer.text(), "a<XY>cdZe");
+    assert_eq!(
+        ranges[0].start.to_offset(&buffer)..ranges[0].end.to_offset(&buffer),
+        2..4
+    );
+}
+
+#[test]
+fn test_anchor_range_biases() {
+    for (start_bias, end_bias, expected_text) in [
+        (Bias::Left, Bias::Right, "<cd>"),
+        (Bias::Right, Bias::Left, "cd"),
+        (Bias::Left, Bias::Left, "<cd"),
+        (Bias::Right, Bias::Right, "cd>"),
+    ] {
+        let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abcdef".into());
+        let range = buffer.anchor_range(2, start_bias, 4, end_bias);
+        buffer.edit([(2..2, "<"), (4..4, ">")]);
+        assert_eq!(buffer.text(), "ab<cd>ef");
+        let range = range.start.to_offset(&buffer)..range.end.to_offset(&buffer);
+        assert_eq!(
+            buffer.text_for_range(range).collect::<String>(),
+            expected_text,
+            "start bias: {start_bias:?}, end bias: {end_bias:?}"
+        );
+    }
+}
+
 #[test]
 fn test_line_endings() {
     assert_eq!(LineEnding::detect(&"🍐✅\n".repeat(1000)), LineEnding::Unix);
@@ -177,6 +253,31 @@ fn test_line_endings() {
         LineEnding::Windows
     );
 
+    assert_eq!(
+        LineEnding::detect_dominant("one\ntwo\r\nthree\r\nfour"),
+        LineEnding::Windows
+    );
+    assert_eq!(
+        LineEnding::detect_dominant("one\r\ntwo\nthree\nfour"),
+        LineEnding::Unix
+    );
+    assert_eq!(
+        LineEnding::detect_dominant("one\r\ntwo\nthree"),
+        LineEnding::Windows
+    );
+    assert_eq!(
+        LineEnding::detect_dominant("one\ntwo\rthree\rfour"),
+        LineEnding::Windows
+    );
+
+    let buffer = Buffer::new_with_dominant_line_ending(
+        0,
+        BufferId::new(1).unwrap(),
+        "one\ntwo\r\nthree\r\nfour".into(),
+    );
+    assert_eq!(buffer.text(), "one\ntwo\nthree\nfour");
+    assert_eq!(buffer.line_ending(), LineEnding::Windows);
+
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "one\r\ntwo\rthree".into());
     assert_eq!(buffer.text(), "one\ntwo\nthree");
     assert_eq!(buffer.line_ending(), LineEnding::Windows);
@@ -189,6 +290,28 @@ fn test_line_endings() {
     buffer.check_invariants();
 }
 
+#[test]
+fn test_buffer_from_rope() {
+    for text in [
+        String::new(),
+        "abc\ndef".to_string(),
+        "abc\r\n".repeat(1000),
+        "abc\r\ndef\rghi\n".repeat(500),
+        "🍐\r".repeat(500),
+    ] {
+        let rope = Rope::from(text.as_str());
+        let buffer = Buffer::from_rope(0, BufferId::new(1).unwrap(), rope);
+        let expected = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+        assert_eq!(buffer.text(), expected.text());
+        assert_eq!(buffer.line_ending(), expected.line_ending());
+        buffer.check_invariants();
+    }
+
+    // Make sure the case where a line ending is split across chunks is covered.
+    let rope = Rope::from("abc\r\n".repeat(1000).as_str());
+    assert!(rope.chunks().any(|chunk| chunk.ends_with('\r')));
+}
+
 #[test]
 fn test_line_len() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -205,6 +328,47 @@ fn test_line_len() {
     assert_eq!(buffer.line_len(5), 0);
 }
 
+#[test]
+fn test_row_and_byte_range_conversions() {
+    let buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc\ndefg\n\nhij".into());
+
+    assert_eq!(buffer.row_range_for_byte_range(0..0), 0..1);
+    assert_eq!(buffer.row_range_for_byte_range(1..3), 0..1);
+    assert_eq!(buffer.row_range_for_byte_range(2..5), 0..2);
+    assert_eq!(buffer.row_range_for_byte_range(4..9), 1..3);
+    assert_eq!(buffer.row_range_for_byte_range(4..10), 1..4);
+    assert_eq!(buffer.row_range_for_byte_range(11..100), 3..4);
+    assert_eq!(buffer.row_range_for_byte_range(100..200), 3..4);
+
+    assert_eq!(buffer.byte_range_for_rows(0..1), 0..4);
+    assert_eq!(buffer.byte_range_for_rows(1..3), 4..10);
+    assert_eq!(buffer.byte_range_for_rows(3..4), 10..13);
+    assert_eq!(buffer.byte_range_for_rows(2..100), 9..13);
+    assert_eq!(buffer.byte_range_for_rows(100..200), 13..13);
+    assert_eq!(buffer.byte_range_for_rows(2..1), 9..9);
+}
+
+#[test]
+fn test_longest_row() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "ab\ncd\nef".into());
+    assert_eq!(buffer.longest_row(), 0);
+
+    buffer.edit([(Point::new(1, 2)..Point::new(1, 2), "cdefg")]);
+    assert_eq!(buffer.text(), "ab\ncdcdefg\nef");
+    assert_eq!(buffer.longest_row(), 1);
+
+    buffer.edit([(Point::new(2, 2)..Point::new(2, 2), "ghijklm")]);
+    assert_eq!(buffer.longest_row(), 2);
+
+    buffer.edit([(Point::new(2, 1)..Point::new(2, 9), "")]);
+    assert_eq!(buffer.text(), "ab\ncdcdefg\ne");
+    assert_eq!(buffer.longest_row(), 1);
+
+    buffer.edit([(Point::new(1, 0)..Point::new(1, 7), "x")]);
+    assert_eq!(buffer.text(), "ab\nx\ne");
+    assert_eq!(buffer.longest_row(), 0);
+}
+
 #[test]
 fn test_common_prefix_at_position() {
     let text = "a = str; b = δα";
@@ -254,6 +418,219 @@ fn test_common_prefix_at_position() {
     }
 }
 
+#[test]
+fn test_rope_for_range() {
+    let line = "fn main() { println!(\"αβγ\"); }\n";
+    let text = line.repeat(4 * 1024 * 1024 / line.len());
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+    buffer.edit([(text.len() / 2..text.len() / 2, "inserted")]);
+
+    let len = buffer.len();
+    let ranges = [
+        0..0,
+        0..len,
+        3..17,
+        len / 3..len / 2 + 100,
+        len / 2 - 7..len / 2 + 50_000,
+        len - 1_000_000..len,
+    ];
+    for range in ranges {
+        let range =
+            buffer.clip_offset(range.start, Bias::Left)..buffer.clip_offset(range.end, Bias::Right);
+        let rope = buffer.rope_for_range(range.clone());
+        let expected = buffer.text_for_range(range.clone()).collect::<String>();
+        assert_eq!(rope.len(), expected.len(), "range {range:?}");
+        assert_eq!(rope.to_string(), expected, "range {range:?}");
+        assert_eq!(
+            rope.summary(),
+            buffer.text_summary_for_range::<TextSummary, _>(range.clone()),
+            "range {range:?}"
+        );
+    }
+}
+
+#[test]
+fn test_chunks_in_rows() {
+    let buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        "one\ntwo\n\nfour\nfive".into(),
+    );
+    let max_row = buffer.max_point().row;
+    for rows in [0..0, 0..1, 1..3, 2..3, 3..5, 0..5, 4..5, 4..10, 7..10, 3..1] {
+        let start = Point::new(rows.start.min(max_row + 1), 0).min(buffer.max_point());
+        let end = if rows.end > max_row {
+            buffer.max_point()
+        } else {
+            Point::new(rows.end, 0)
+        }
+        .max(start);
+        let expected = buffer.text_for_range(start..end).collect::<String>();
+        assert_eq!(
+            buffer.chunks_in_rows(rows.clone()).collect::<String>(),
+            expected,
+            "rows {rows:?}"
+        );
+    }
+    assert_eq!(buffer.chunks_in_rows(1..3).collect::<String>(), "two\n\n");
+    assert_eq!(buffer.chunks_in_rows(4..5).collect::<String>(), "five");
+}
+
+#[test]
+fn test_bytes_in_range() {
+    let text = "one two three\nfour five 🍐 six\nseven".repeat(8);
+    let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+    for start in (0..=text.len()).filter(|ix| text.is_char_boundary(*ix)) {
+        for end in (start..=text.len()).filter(|ix| text.is_char_boundary(*ix)) {
+            assert_eq!(
+                buffer
+                    .bytes_in_range(start..end)
+                    .flatten()
+                    .copied()
+                    .collect::<Vec<_>>(),
+                buffer
+                    .text_for_range(start..end)
+                    .collect::<String>()
+                    .into_bytes(),
+                "range {start}..{end}"
+            );
+        }
+    }
+
+    assert!(buffer.contains_str_at(4, "two"));
+    assert!(buffer.contains_str_at(text.len() - 5, "seven"));
+    assert!(!buffer.contains_str_at(text.len() - 5, "seven!"));
+    assert!(!buffer.contains_str_at(4, "twice"));
+}
+
+#[test]
+fn test_find_all() {
+    let mut text = String::new();
+    let mut row = 0;
+    while text.len() < 3 * FIND_ALL_WINDOW_LEN {
+        text.push_str(&format!("line {row} foo\n"));
+        row += 1;
+    }
+    // Sprinkle needles throughout the text so that some of them straddle
+    // rope chunk boundaries, as well as the boundaries of the search windows.
+    let needle_offsets = (0..text.len()).step_by(997).collect::<Vec<_>>();
+    for offset in needle_offsets.into_iter().rev() {
+        text.insert_str(offset, "neeedle");
+    }
+    let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+
+    let mut chunk_boundaries = HashSet::default();
+    let mut offset = 0;
+    for chunk in buffer.as_rope().chunks() {
+        offset += chunk.len();
+        chunk_boundaries.insert(offset);
+    }
+
+    for pattern in [
+        "ne+dle",
+        r"\bfoo\b",
+        r"(?m)^line \d+",
+        "foo\nline",
+        r"\d+ f",
+    ] {
+        let regex = Regex::new(pattern).unwrap();
+        let expected = regex
+            .find_iter(&text)
+            .map(|mat| mat.range())
+            .collect::<Vec<_>>();
+        assert_eq!(buffer.find_all(&regex), expected, "pattern {pattern:?}");
+    }
+
+    let needles = buffer.find_all(&Regex::new("ne+dle").unwrap());
+    assert!(needles
+        .iter()
+        .any(|range| (range.start + 1..range.end).any(|ix| chunk_boundaries.contains(&ix))));
+
+    // Matches longer than a window are found in full.
+    let text = format!(
+        "b{}b\n{}",
+        "a".repeat(2 * FIND_ALL_WINDOW_LEN),
+        "c".repeat(100)
+    );
+    let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+    for pattern in ["a+", ".+", "c+"] {
+        let regex = Regex::new(pattern).unwrap();
+        let expected = regex
+            .find_iter(&text)
+            .map(|mat| mat.range())
+            .collect::<Vec<_>>();
+        assert_eq!(buffer.find_all(&regex), expected, "pattern {pattern:?}");
+    }
+}
+
+#[test]
+fn test_preview_edits() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc\ndefg\nhij".into());
+    let anchors = [
+        buffer.anchor_before(0),
+        buffer.anchor_before(1),
+        buffer.anchor_after(1),
+        buffer.anchor_after(5),
+        buffer.anchor_before(8),
+        buffer.anchor_after(12),
+    ];
+    let snapshot = buffer.snapshot().clone();
+    let edits = [(1..1, "XY"), (4..8, "Z"), (10..12, "\nkl\nmn")];
+
+    let preview = snapshot.preview_edits(edits.clone(), &anchors);
+    buffer.edit(edits);
+    assert_eq!(buffer.text(), "aXYbc\nZ\nh\nkl\nmn");
+    assert_eq!(preview.len, buffer.len());
+    assert_eq!(
+        preview.summary,
+        buffer.text_summary_for_range(0..buffer.len())
+    );
+    assert_eq!(preview.anchor_offsets, [0, 1, 3, 7, 7, 15]);
+    assert_eq!(
+        preview.anchor_offsets,
+        buffer
+            .summaries_for_anchors::<usize, _>(&anchors)
+            .collect::<Vec<_>>()
+    );
+    // Previewing doesn't modify the snapshot it was computed from.
+    assert_eq!(snapshot.text(), "abc\ndefg\nhij");
+}
+
+#[gpui::test(iterations = 100)]
+fn test_random_preview_edits(mut rng: StdRng) {
+    let text_len = rng.gen_range(0..50);
+    let text = RandomCharIter::new(&mut rng)
+        .take(text_len)
+        .collect::<String>();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), text);
+
+    for _ in 0..5 {
+        let anchors = (0..10)
+            .map(|_| {
+                let offset = buffer.clip_offset(rng.gen_range(0..=buffer.len()), Bias::Left);
+                let bias = if rng.gen() { Bias::Left } else { Bias::Right };
+                buffer.anchor_at(offset, bias)
+            })
+            .collect::<Vec<_>>();
+        let snapshot = buffer.snapshot().clone();
+        let (edits, _) = buffer.randomly_edit(&mut rng, 5);
+        log::info!("previewing {:?} on {:?}", edits, snapshot.text());
+
+        let preview = snapshot.preview_edits(edits, &anchors);
+        assert_eq!(preview.len, buffer.len());
+        assert_eq!(
+            preview.summary,
+            buffer.text_summary_for_range(0..buffer.len())
+        );
+        assert_eq!(
+            preview.anchor_offsets,
+            buffer
+                .summaries_for_anchors::<usize, _>(&anchors)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
 #[test]
 fn test_text_summary_for_range() {
     let buffer = Buffer::new(
@@ -360,6 +737,67 @@ fn test_chars_at() {
     assert_eq!(chars.collect::<String>(), "    \"xray_wasm\",\n]\n");
 }
 
+#[gpui::test(iterations = 100)]
+fn test_random_summaries_for_unordered_anchors(mut rng: StdRng) {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.randomly_edit(&mut rng, 5);
+
+    let mut anchors = Vec::new();
+    for _ in 0..rng.gen_range(0..5) {
+        for _ in 0..rng.gen_range(1..10) {
+            let offset = buffer.clip_offset(rng.gen_range(0..=buffer.len()), Bias::Left);
+            anchors.push(if rng.gen() {
+                buffer.anchor_before(offset)
+            } else {
+                buffer.anchor_after(offset)
+            });
+        }
+        buffer.randomly_edit(&mut rng, 3);
+    }
+    anchors.shuffle(&mut rng);
+
+    assert_eq!(
+        buffer.summaries_for_unordered_anchors::<usize>(&anchors),
+        anchors
+            .iter()
+            .map(|anchor| anchor.to_offset(&buffer))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        buffer.summaries_for_unordered_anchors::<Point>(&anchors),
+        anchors
+            .iter()
+            .map(|anchor| anchor.to_point(&buffer))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[gpui::test(iterations = 100)]
+fn test_random_offset_and_point_for_anchor(mut rng: StdRng) {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.randomly_edit(&mut rng, 5);
+
+    let mut anchors = vec![Anchor::MIN, Anchor::MAX];
+    for _ in 0..rng.gen_range(0..5) {
+        for _ in 0..rng.gen_range(1..10) {
+            let offset = buffer.clip_offset(rng.gen_range(0..=buffer.len()), Bias::Left);
+            anchors.push(if rng.gen() {
+                buffer.anchor_before(offset)
+            } else {
+                buffer.anchor_after(offset)
+            });
+        }
+        buffer.randomly_edit(&mut rng, 3);
+    }
+
+    for anchor in &anchors {
+        assert_eq!(
+            buffer.offset_and_point_for_anchor(anchor),
+            (anchor.to_offset(&buffer), anchor.to_point(&buffer))
+        );
+    }
+}
+
 #[test]
 fn test_anchors() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -537,6 +975,59 @@ fn test_undo_redo() {
     assert_eq!(buffer.text(), "1234");
 }
 
+#[test]
+fn test_diff_since() {
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        "one\ntwo\nthree\nfour\nfive\nsix\n".into(),
+    );
+    buffer.set_group_interval(Duration::from_secs(0));
+    let version = buffer.version();
+
+    buffer.edit([(Point::new(0, 1)..Point::new(0, 3), "NE")]);
+    buffer.edit([(Point::new(2, 0)..Point::new(2, 0), "2.5\n")]);
+    buffer.edit([(Point::new(5, 0)..Point::new(6, 0), "")]);
+    buffer.edit([(Point::new(5, 0)..Point::new(5, 3), "SIX")]);
+    assert_eq!(buffer.text(), "oNE\ntwo\n2.5\nthree\nfour\nSIX\n");
+    assert_eq!(
+        buffer.diff_since(&version),
+        [
+            Edit {
+                old: 0..1,
+                new: 0..1,
+            },
+            Edit {
+                old: 2..2,
+                new: 2..3,
+            },
+            Edit {
+                old: 4..6,
+                new: 5..6,
+            },
+        ]
+    );
+    assert!(buffer.diff_since(&buffer.version()).is_empty());
+
+    // Undone edits are excluded from the diff.
+    let inserted_line = buffer.history.undo_stack[1].transaction.clone();
+    buffer.undo_or_redo(inserted_line);
+    assert_eq!(buffer.text(), "oNE\ntwo\nthree\nfour\nSIX\n");
+    assert_eq!(
+        buffer.diff_since(&version),
+        [
+            Edit {
+                old: 0..1,
+                new: 0..1,
+            },
+            Edit {
+                old: 4..6,
+                new: 4..5,
+            },
+        ]
+    );
+}
+
 #[test]
 fn test_history() {
     let mut now = Instant::now();
@@ -604,6 +1095,48 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_transaction_descriptions() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+    buffer.set_group_interval(Duration::from_millis(300));
+
+    let transaction_1 = buffer.start_transaction_at(now).unwrap();
+    buffer.set_transaction_description(transaction_1, "Sort Lines");
+    buffer.edit([(0..1, "a")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(
+        buffer.peek_undo_stack().unwrap().description(),
+        Some("Sort Lines")
+    );
+
+    // When transactions are grouped, the first description wins.
+    let transaction_2 = buffer.start_transaction_at(now).unwrap();
+    buffer.set_transaction_description(transaction_2, "Reverse Lines");
+    buffer.edit([(1..2, "b")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(
+        buffer.peek_undo_stack().unwrap().transaction_id(),
+        transaction_1
+    );
+    assert_eq!(
+        buffer.peek_undo_stack().unwrap().description(),
+        Some("Sort Lines")
+    );
+
+    now += buffer.transaction_group_interval() + Duration::from_millis(1);
+    buffer.start_transaction_at(now);
+    buffer.edit([(2..3, "c")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(buffer.peek_undo_stack().unwrap().description(), None);
+
+    buffer.undo();
+    assert_eq!(
+        buffer.peek_undo_stack().unwrap().description(),
+        Some("Sort Lines")
+    );
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -640,6 +1173,31 @@ fn test_finalize_last_transaction() {
     assert_eq!(buffer.text(), "ab2cde6");
 }
 
+#[test]
+fn test_merge_last_two_transactions() {
+    let now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "123456".into());
+    buffer.history.group_interval = Duration::from_millis(1);
+    assert_eq!(buffer.merge_last_two_transactions(), None);
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(0..1, "a")]);
+    let first_id = buffer.end_transaction_at(now).unwrap().0;
+    assert_eq!(buffer.merge_last_two_transactions(), None);
+
+    buffer.finalize_last_transaction();
+    buffer.start_transaction_at(now + Duration::from_secs(1));
+    buffer.edit([(1..2, "b")]);
+    buffer.end_transaction_at(now + Duration::from_secs(1));
+    assert_eq!(buffer.text(), "ab3456");
+
+    assert_eq!(buffer.merge_last_two_transactions(), Some(first_id));
+    buffer.undo();
+    assert_eq!(buffer.text(), "123456");
+    buffer.redo();
+    assert_eq!(buffer.text(), "ab3456");
+}
+
 #[test]
 fn test_edited_ranges_for_transaction() {
     let now = Instant::now();
@@ -46,6 +46,20 @@ use util::RandomCharIter;
 static LINE_SEPARATORS_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\r\n|\r").expect("Failed to create LINE_SEPARATORS_REGEX"));
 
+/// How much text [`BufferSnapshot::find_all`] searches at a time.
+const FIND_ALL_WINDOW_LEN: usize = 64 * 1024;
+/// How much text [`BufferSnapshot::find_all`] keeps from one window to the next, so that
+/// matches crossing a window boundary are still found.
+const FIND_ALL_OVERLAP: usize = 4 * 1024;
+
+/// The default maximum time between transactions for them to be undone together.
+// Don't group transactions in tests unless we opt in, because it's a footgun.
+#[cfg(any(test, feature = "test-support"))]
+pub const DEFAULT_GROUP_INTERVAL: Duration = Duration::ZERO;
+/// The default maximum time between transactions for them to be undone together.
+#[cfg(not(any(test, feature = "test-support")))]
+pub const DEFAULT_GROUP_INTERVAL: Duration = Duration::from_millis(300);
+
 pub type TransactionId = clock::Lamport;
 
 pub struct Buffer {
@@ -126,12 +140,17 @@ pub struct Transaction {
     pub id: TransactionId,
     pub edit_ids: Vec<clock::Lamport>,
     pub start: clock::Global,
+    pub description: Option<Arc<str>>,
 }
 
 impl HistoryEntry {
     pub fn transaction_id(&self) -> TransactionId {
         self.transaction.id
     }
+
+    pub fn description(&self) -> Option<&str> {
+        self.transaction.description.as_deref()
+    }
 }
 
 struct History {
@@ -159,11 +178,7 @@ impl History {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             transaction_depth: 0,
-            // Don't group transactions in tests unless we opt in, because it's a footgun.
-            #[cfg(any(test, feature = "test-support"))]
-            group_interval: Duration::ZERO,
-            #[cfg(not(any(test, feature = "test-support")))]
-            group_interval: Duration::from_millis(300),
+            group_interval: DEFAULT_GROUP_INTERVAL,
         }
     }
 
@@ -185,6 +200,7 @@ impl History {
                     id,
                     start,
                     edit_ids: Default::default(),
+                    description: None,
                 },
                 first_edit_at: now,
                 last_edit_at: now,
@@ -261,6 +277,9 @@ impl History {
                 for edit_id in &entry.transaction.edit_ids {
                     last_entry.transaction.edit_ids.push(*edit_id);
                 }
+                if last_entry.transaction.description.is_none() {
+                    last_entry.transaction.description = entry.transaction.description.clone();
+                }
             }
 
             if let Some(entry) = entries_to_merge.last_mut() {
@@ -272,6 +291,20 @@ impl History {
         self.undo_stack.last().map(|e| e.transaction.id)
     }
 
+    fn merge_last_two(&mut self) -> Option<TransactionId> {
+        assert_eq!(self.transaction_depth, 0);
+        if self.undo_stack.len() < 2 {
+            return None;
+        }
+
+        let suppress_grouping = self.undo_stack.last()?.suppress_grouping;
+        let transaction_id = self.group_trailing(1);
+        if let Some(entry) = self.undo_stack.last_mut() {
+            entry.suppress_grouping = suppress_grouping;
+        }
+        transaction_id
+    }
+
     fn finalize_last_transaction(&mut self) -> Option<&Transaction> {
         self.undo_stack.last_mut().map(|entry| {
             entry.suppress_grouping = true;
@@ -384,6 +417,9 @@ impl History {
         if let Some(transaction) = self.forget(transaction) {
             if let Some(destination) = self.transaction_mut(destination) {
                 destination.edit_ids.extend(transaction.edit_ids);
+                if destination.description.is_none() {
+                    destination.description = transaction.description;
+                }
             }
         }
     }
@@ -449,6 +485,17 @@ where
     }
 }
 
+/// The result of [`BufferSnapshot::preview_edits`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EditPreview {
+    /// The length of the text after the edits.
+    pub len: usize,
+    /// The offset that each of the given anchors would resolve to after the edits.
+    pub anchor_offsets: Vec<usize>,
+    /// The summary of the text after the edits.
+    pub summary: TextSummary,
+}
+
 impl<D1, D2> Edit<(D1, D2)> {
     pub fn flatten(self) -> (Edit<D1>, Edit<D2>) {
         (
@@ -650,6 +697,57 @@ impl Buffer {
         Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text))
     }
 
+    /// Like [`Buffer::new`], but gives the buffer whichever line ending is most
+    /// common in `base_text`, rather than the first one.
+    pub fn new_with_dominant_line_ending(
+        replica_id: u16,
+        remote_id: BufferId,
+        mut base_text: String,
+    ) -> Buffer {
+        let line_ending = LineEnding::detect_dominant(&base_text);
+        LineEnding::normalize(&mut base_text);
+        Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text))
+    }
+
+    /// Creates a buffer from a rope that may not have normalized line endings,
+    /// e.g. one built incrementally while streaming a large file, without
+    /// collecting it into a single string first.
+    pub fn from_rope(replica_id: u16, remote_id: BufferId, base_text: Rope) -> Buffer {
+        let mut prefix = String::new();
+        for chunk in base_text.chunks() {
+            prefix.push_str(chunk);
+            if prefix.len() >= 1000 {
+                break;
+            }
+        }
+        let line_ending = LineEnding::detect(&prefix);
+
+        let normalized = if base_text.chunks().any(|chunk| chunk.contains('\r')) {
+            let mut normalized = Rope::new();
+            let mut skip_leading_newline = false;
+            for mut chunk in base_text.chunks() {
+                if skip_leading_newline {
+                    chunk = chunk.strip_prefix('\n').unwrap_or(chunk);
+                }
+                // A `\r\n` may be split across two chunks.
+                skip_leading_newline = chunk.ends_with('\r');
+                if skip_leading_newline {
+                    chunk = &chunk[..chunk.len() - 1];
+                }
+
+                normalized.push(&LineEnding::normalize_cow(Cow::Borrowed(chunk)));
+                if skip_leading_newline {
+                    normalized.push("\n");
+                }
+            }
+            normalized
+        } else {
+            base_text
+        };
+
+        Self::new_normalized(replica_id, remote_id, line_ending, normalized)
+    }
+
     pub fn new_normalized(
         replica_id: u16,
         remote_id: BufferId,
@@ -766,6 +864,80 @@ impl Buffer {
         operation
     }
 
+    /// Applies the given edits like [`Buffer::edit`], returning anchor ranges that
+    /// cover the text inserted by each edit, in the order the edits were given.
+    /// The edits must be sorted and non-overlapping.
+    pub fn edit_returning_ranges<R, I, S, T>(&mut self, edits: R) -> (Operation, Vec<Range<Anchor>>)
+    where
+        R: IntoIterator<IntoIter = I>,
+        I: ExactSizeIterator<Item = (Range<S>, T)>,
+        S: ToOffset,
+        T: Into<Arc<str>>,
+    {
+        let edits = edits
+            .into_iter()
+            .map(|(range, new_text)| {
+                (
+                    range.start.to_offset(self)..range.end.to_offset(self),
+                    new_text.into(),
+                )
+            })
+            .collect::<Vec<(Range<usize>, Arc<str>)>>();
+
+        let mut delta = 0isize;
+        let mut new_ranges = Vec::with_capacity(edits.len());
+        for (range, new_text) in &edits {
+            let start = (range.start as isize + delta) as usize;
+            new_ranges.push(start..start + new_text.len());
+            delta += new_text.len() as isize - range.len() as isize;
+        }
+
+        let operation = self.edit(edits);
+        let anchor_ranges = new_ranges
+            .into_iter()
+            .map(|range| {
+                if range.is_empty() {
+                    let anchor = self.anchor_before(range.start);
+                    anchor..anchor
+                } else {
+                    self.anchor_after(range.start)..self.anchor_before(range.end)
+                }
+            })
+            .collect();
+        (operation, anchor_ranges)
+    }
+
+    /// Replaces the buffer's contents with the given text, editing only the
+    /// region between the common prefix and suffix of the old and new text
+    /// so that anchors outside of it keep their positions.
+    pub fn set_text(&mut self, text: &str) -> Operation {
+        let old_len = self.len();
+        let max_common_len = old_len.min(text.len());
+
+        let mut prefix_len = 0;
+        for (old_char, new_char) in self.chars().zip(text.chars()) {
+            if old_char != new_char {
+                break;
+            }
+            prefix_len += new_char.len_utf8();
+        }
+
+        let mut suffix_len = 0;
+        for (old_char, new_char) in self.reversed_chars_at(old_len).zip(text.chars().rev()) {
+            if old_char != new_char
+                || prefix_len + suffix_len + new_char.len_utf8() > max_common_len
+            {
+                break;
+            }
+            suffix_len += new_char.len_utf8();
+        }
+
+        self.edit([(
+            prefix_len..old_len - suffix_len,
+            &text[prefix_len..text.len() - suffix_len],
+        )])
+    }
+
     fn apply_local_edit<S: ToOffset, T: Into<Arc<str>>>(
         &mut self,
         edits: impl ExactSizeIterator<Item = (Range<S>, T)>,
@@ -1317,6 +1489,13 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    pub fn undo_stack_contains(&self, transaction_id: TransactionId) -> bool {
+        self.history
+            .undo_stack
+            .iter()
+            .any(|entry| entry.transaction.id == transaction_id)
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -1398,6 +1577,25 @@ impl Buffer {
         self.history.merge_transactions(transaction, destination);
     }
 
+    /// Merges the two most recent transactions on the undo stack into one,
+    /// even if they were finalized or too far apart in time to be grouped.
+    /// Returns the id of the merged transaction.
+    pub fn merge_last_two_transactions(&mut self) -> Option<TransactionId> {
+        self.history.merge_last_two()
+    }
+
+    /// Labels a transaction, e.g. so that its undo can be shown as "Undo Sort Lines".
+    /// When transactions are grouped, the earliest description is kept.
+    pub fn set_transaction_description(
+        &mut self,
+        transaction_id: TransactionId,
+        description: impl Into<Arc<str>>,
+    ) {
+        if let Some(transaction) = self.history.transaction_mut(transaction_id) {
+            transaction.description = Some(description.into());
+        }
+    }
+
     pub fn redo(&mut self) -> Option<(TransactionId, Operation)> {
         if let Some(entry) = self.history.pop_redo() {
             let transaction = entry.transaction.clone();
@@ -1681,6 +1879,26 @@ impl Buffer {
                 "fragment: {:?}\ninsertion: {:?}",
                 fragment, insertion_fragment
             );
+
+            // A fragment is visible exactly when its insertion hasn't been undone and
+            // all of its deletions have, and every operation it reflects must have
+            // been observed by the buffer's version.
+            assert_eq!(
+                fragment.visible,
+                fragment.is_visible(&self.snapshot.undo_map),
+                "fragment: {:?}",
+                fragment
+            );
+            assert!(
+                self.snapshot.version.observed(fragment.timestamp)
+                    && fragment
+                        .deletions
+                        .iter()
+                        .all(|deletion| self.snapshot.version.observed(*deletion)),
+                "fragment: {:?}\nversion: {:?}",
+                fragment,
+                self.snapshot.version
+            );
         }
 
         let mut cursor = self.snapshot.fragments.cursor::<Option<&Locator>>(&None);
@@ -1705,7 +1923,12 @@ impl Buffer {
     }
 
     pub fn set_group_interval(&mut self, group_interval: Duration) {
-        self.history.group_interval = group_interval;
+        if group_interval != self.history.group_interval {
+            // Don't let transactions that were committed under the previous
+            // interval be grouped with new ones.
+            self.history.finalize_last_transaction();
+            self.history.group_interval = group_interval;
+        }
     }
 
     pub fn random_byte_range(&self, start_offset: usize, rng: &mut impl rand::Rng) -> Range<usize> {
@@ -1880,12 +2103,12 @@ impl BufferSnapshot {
         T: ToOffset,
     {
         let position = position.to_offset(self);
+        let end = cmp::min(position + needle.len(), self.len());
         position == self.clip_offset(position, Bias::Left)
             && self
-                .bytes_in_range(position..self.len())
+                .bytes_in_range(position..end)
                 .flatten()
                 .copied()
-                .take(needle.len())
                 .eq(needle.bytes())
     }
 
@@ -1941,6 +2164,14 @@ impl BufferSnapshot {
         self.visible_text.max_point_utf16()
     }
 
+    /// Returns the row containing the most characters.
+    ///
+    /// This is read from the rope's cached summary, so it doesn't require
+    /// scanning the buffer.
+    pub fn longest_row(&self) -> u32 {
+        self.visible_text.summary().longest_row
+    }
+
     pub fn point_to_offset(&self, point: Point) -> usize {
         self.visible_text.point_to_offset(point)
     }
@@ -1977,6 +2208,23 @@ impl BufferSnapshot {
         self.visible_text.point_to_point_utf16(point)
     }
 
+    /// Returns the rows intersecting the given byte range, as an exclusive range.
+    /// Offsets past the end of the buffer are clipped rather than panicking.
+    pub fn row_range_for_byte_range(&self, range: Range<usize>) -> Range<u32> {
+        let start = self.offset_to_point(self.clip_offset(range.start, Bias::Left));
+        let end = self.offset_to_point(self.clip_offset(range.end, Bias::Right));
+        start.row..cmp::max(start.row, end.row) + 1
+    }
+
+    /// Returns the byte range from the start of `rows.start` to the start of
+    /// `rows.end`, including the trailing newline of the last row. Rows past the
+    /// end of the buffer are clipped rather than panicking.
+    pub fn byte_range_for_rows(&self, rows: Range<u32>) -> Range<usize> {
+        let start = self.point_to_offset(self.clip_point(Point::new(rows.start, 0), Bias::Left));
+        let end = self.point_to_offset(self.clip_point(Point::new(rows.end, 0), Bias::Left));
+        start..cmp::max(start, end)
+    }
+
     pub fn version(&self) -> &clock::Global {
         &self.version
     }
@@ -2014,6 +2262,99 @@ impl BufferSnapshot {
         self.visible_text.chunks_in_range(start..end)
     }
 
+    /// Returns the text of the given rows, including each row's trailing newline.
+    ///
+    /// Rows past the end of the buffer are clamped, and the last row is returned
+    /// without a newline if the buffer doesn't end with one.
+    pub fn chunks_in_rows(&self, rows: Range<u32>) -> Chunks<'_> {
+        let max_point = self.max_point();
+        let offset_for_row = |row: u32| {
+            if row > max_point.row {
+                self.len()
+            } else {
+                self.visible_text.point_to_offset(Point::new(row, 0))
+            }
+        };
+        let start = offset_for_row(rows.start);
+        let end = offset_for_row(rows.end).max(start);
+        self.visible_text.chunks_in_range(start..end)
+    }
+
+    /// Returns the visible text in the given range as an owned [`Rope`]. The
+    /// rope's chunks are reused from the buffer rather than re-collected.
+    pub fn rope_for_range<T: ToOffset>(&self, range: Range<T>) -> Rope {
+        let start = range.start.to_offset(self);
+        let end = range.end.to_offset(self);
+        self.visible_text.slice(start..end)
+    }
+
+    /// Returns the ranges of all non-overlapping matches of `regex` in the visible text.
+    ///
+    /// Rather than collecting the whole text into a string, this searches it in windows
+    /// of rope chunks. A match that runs to the end of a window is searched for again
+    /// once more text has been read, so that it isn't cut short.
+    pub fn find_all(&self, regex: &Regex) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        let mut chunks = self.visible_text.chunks();
+        let mut window = String::new();
+        let mut window_start = 0;
+        let mut search_ix = 0;
+        let mut min_window_len = FIND_ALL_WINDOW_LEN;
+        loop {
+            let mut done = false;
+            while window.len() < search_ix + min_window_len {
+                if let Some(chunk) = chunks.next() {
+                    window.push_str(chunk);
+                } else {
+                    done = true;
+                    break;
+                }
+            }
+            min_window_len = FIND_ALL_WINDOW_LEN;
+
+            let safe_len = window.len().saturating_sub(FIND_ALL_OVERLAP);
+            while search_ix <= window.len() {
+                let Some(mat) = regex.find_at(&window, search_ix) else {
+                    search_ix = cmp::max(search_ix, safe_len);
+                    break;
+                };
+                // This match might continue into the next window, so read another window's
+                // worth of text before searching from here again.
+                if !done && mat.end() > safe_len {
+                    min_window_len = window.len() - search_ix + FIND_ALL_WINDOW_LEN;
+                    break;
+                }
+
+                matches.push(window_start + mat.start()..window_start + mat.end());
+                search_ix = if mat.is_empty() {
+                    let next_char = window[mat.end()..].chars().next();
+                    mat.end() + next_char.map_or(1, char::len_utf8)
+                } else {
+                    mat.end()
+                };
+            }
+
+            if done {
+                break;
+            }
+
+            // Drop text that has already been searched, keeping one character so that
+            // assertions like `\b` still see what precedes the next search.
+            let mut drain_ix = cmp::min(search_ix, safe_len);
+            while !window.is_char_boundary(drain_ix) {
+                drain_ix -= 1;
+            }
+            drain_ix = window[..drain_ix]
+                .char_indices()
+                .next_back()
+                .map_or(0, |(ix, _)| ix);
+            window.drain(..drain_ix);
+            window_start += drain_ix;
+            search_ix -= drain_ix;
+        }
+        matches
+    }
+
     pub fn line_len(&self, row: u32) -> u32 {
         let row_start_offset = Point::new(row, 0).to_offset(self);
         let row_end_offset = if row >= self.max_point().row {
@@ -2109,6 +2450,79 @@ impl BufferSnapshot {
             .summary(range.end.to_offset(self))
     }
 
+    /// Computes what applying the given edits would do to the buffer, without
+    /// mutating it or generating any operations.
+    ///
+    /// The resulting anchor offsets are the ones the anchors would resolve to in
+    /// the edited buffer. Anchors inside of a replaced range end up after its new
+    /// text, and anchors at an insertion point move past it unless they're
+    /// biased to the left.
+    pub fn preview_edits<R, S, T>(&self, edits: R, anchors: &[Anchor]) -> EditPreview
+    where
+        R: IntoIterator<Item = (Range<S>, T)>,
+        S: ToOffset,
+        T: AsRef<str>,
+    {
+        let mut edits = edits
+            .into_iter()
+            .map(|(range, new_text)| {
+                let start = range.start.to_offset(self);
+                let end = range.end.to_offset(self);
+                (start..end, new_text)
+            })
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let mut summary = TextSummary::default();
+        let mut patch = Vec::with_capacity(edits.len());
+        let mut old_offset = 0;
+        let mut new_offset = 0;
+        for (range, new_text) in &edits {
+            let old_start = cmp::max(range.start, old_offset);
+            let old_end = cmp::max(range.end, old_start);
+            let new_text = new_text.as_ref();
+
+            summary += self.text_summary_for_range::<TextSummary, _>(old_offset..old_start);
+            summary += TextSummary::from(new_text);
+
+            let new_start = new_offset + (old_start - old_offset);
+            let new_end = new_start + new_text.len();
+            patch.push(Edit {
+                old: old_start..old_end,
+                new: new_start..new_end,
+            });
+            old_offset = old_end;
+            new_offset = new_end;
+        }
+        summary += self.text_summary_for_range::<TextSummary, _>(old_offset..self.len());
+
+        let anchor_offsets = anchors
+            .iter()
+            .map(|anchor| {
+                let offset = anchor.to_offset(self);
+                let mut new_offset = offset;
+                for edit in &patch {
+                    if offset < edit.old.start
+                        || (offset == edit.old.start && anchor.bias == Bias::Left)
+                    {
+                        break;
+                    } else if offset <= edit.old.end {
+                        new_offset = edit.new.end;
+                    } else {
+                        new_offset = edit.new.end + (offset - edit.old.end);
+                    }
+                }
+                new_offset
+            })
+            .collect();
+
+        EditPreview {
+            len: summary.len,
+            anchor_offsets,
+            summary,
+        }
+    }
+
     pub fn summaries_for_anchors<'a, D, A>(&'a self, anchors: A) -> impl 'a + Iterator<Item = D>
     where
         D: 'a + TextDimension,
@@ -2119,6 +2533,34 @@ impl BufferSnapshot {
             .map(|d| d.0)
     }
 
+    /// Like [`Self::summaries_for_anchors`], but accepts anchors in any order,
+    /// returning their summaries in the order the anchors were given.
+    pub fn summaries_for_unordered_anchors<D>(&self, anchors: &[Anchor]) -> Vec<D>
+    where
+        D: TextDimension,
+    {
+        // Resolve each anchor's fragment once up front, rather than on every comparison
+        // as `Anchor::cmp` would.
+        let mut sorted_anchors = anchors
+            .iter()
+            .enumerate()
+            .map(|(ix, anchor)| (self.fragment_id_for_anchor(anchor), anchor, ix))
+            .collect::<Vec<_>>();
+        sorted_anchors.sort_unstable_by_key(|(fragment_id, anchor, _)| {
+            (*fragment_id, anchor.offset, anchor.bias)
+        });
+
+        let mut summaries = vec![D::zero(&()); anchors.len()];
+        for (summary, ix) in self.summaries_for_anchors_with_payload::<D, _, _>(
+            sorted_anchors
+                .into_iter()
+                .map(|(_, anchor, ix)| (anchor, ix)),
+        ) {
+            summaries[ix] = summary;
+        }
+        summaries
+    }
+
     pub fn summaries_for_anchors_with_payload<'a, D, A, T>(
         &'a self,
         anchors: A,
@@ -2172,6 +2614,12 @@ impl BufferSnapshot {
         })
     }
 
+    /// Resolves both the offset and the point of the given anchor with a single
+    /// traversal of the buffer's fragments.
+    pub fn offset_and_point_for_anchor(&self, anchor: &Anchor) -> (usize, Point) {
+        self.summary_for_anchor(anchor)
+    }
+
     fn summary_for_anchor<D>(&self, anchor: &Anchor) -> D
     where
         D: TextDimension,
@@ -2275,6 +2723,27 @@ impl BufferSnapshot {
         self.anchor_at_offset(position.to_offset(self), bias)
     }
 
+    /// Returns an anchor range whose endpoints are biased independently. The biases
+    /// decide what happens to text inserted exactly at either end of the range:
+    ///
+    /// - `(Left, Right)`: the range grows to include insertions at both ends.
+    /// - `(Right, Left)`: insertions at both ends stay outside of the range, so it
+    ///   only grows when text is inserted strictly inside it. An empty range created
+    ///   this way ends up reversed after an insertion at its position.
+    /// - `(Left, Left)`: insertions at the start are included, while insertions at
+    ///   the end stay outside.
+    /// - `(Right, Right)`: insertions at the start stay outside, while insertions at
+    ///   the end are included.
+    pub fn anchor_range<T: ToOffset>(
+        &self,
+        start: T,
+        start_bias: Bias,
+        end: T,
+        end_bias: Bias,
+    ) -> Range<Anchor> {
+        self.anchor_at(start, start_bias)..self.anchor_at(end, end_bias)
+    }
+
     fn anchor_at_offset(&self, offset: usize, bias: Bias) -> Anchor {
         if bias == Bias::Left && offset == 0 {
             Anchor::MIN
@@ -2326,6 +2795,37 @@ impl BufferSnapshot {
         self.edits_since_in_range(since, Anchor::MIN..Anchor::MAX)
     }
 
+    /// Returns the rows that changed since the given version, as hunks of
+    /// whole lines. Each hunk's `old` range contains rows in the buffer as of
+    /// `since`, and its `new` range contains the rows that replaced them.
+    /// Hunks that touch or overlap are coalesced.
+    pub fn diff_since(&self, since: &clock::Global) -> Vec<Edit<u32>> {
+        let mut hunks: Vec<Edit<u32>> = Vec::new();
+        for edit in self.edits_since::<Point>(since) {
+            // An edit covering whole lines leaves the row at its end untouched.
+            let covers_whole_lines = edit.old.start.column == 0
+                && edit.old.end.column == 0
+                && edit.new.start.column == 0
+                && edit.new.end.column == 0;
+            let trailing_row = if covers_whole_lines { 0 } else { 1 };
+            let hunk = Edit {
+                old: edit.old.start.row..edit.old.end.row + trailing_row,
+                new: edit.new.start.row..edit.new.end.row + trailing_row,
+            };
+
+            if let Some(last_hunk) = hunks.last_mut() {
+                if hunk.old.start <= last_hunk.old.end {
+                    let old_end = cmp::max(last_hunk.old.end, hunk.old.end);
+                    last_hunk.new.end = hunk.new.end + (old_end - hunk.old.end);
+                    last_hunk.old.end = old_end;
+                    continue;
+                }
+            }
+            hunks.push(hunk);
+        }
+        hunks
+    }
+
     pub fn anchored_edits_since<'a, D>(
         &'a self,
         since: &'a clock::Global,
@@ -3112,6 +3612,33 @@ impl LineEnding {
         }
     }
 
+    /// Like [`LineEnding::detect`], but considers every line in the text and
+    /// returns whichever line ending is most common. This is useful when
+    /// normalizing text that mixes line endings. A lone `\r`, which
+    /// [`LineEnding::normalize`] also treats as a line break, counts towards
+    /// [`LineEnding::Windows`].
+    pub fn detect_dominant(text: &str) -> Self {
+        let mut windows = 0;
+        let mut unix = 0;
+        let mut bytes = text.bytes().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'\r' => {
+                    bytes.next_if_eq(&b'\n');
+                    windows += 1;
+                }
+                b'\n' => unix += 1,
+                _ => {}
+            }
+        }
+
+        match windows.cmp(&unix) {
+            cmp::Ordering::Greater => Self::Windows,
+            cmp::Ordering::Less => Self::Unix,
+            cmp::Ordering::Equal => Self::detect(text),
+        }
+    }
+
     pub fn normalize(text: &mut String) {
         if let Cow::Owned(replaced) = LINE_SEPARATORS_REGEX.replace_all(text, "\n") {
             *text = replaced;
@@ -2,7 +2,9 @@ use anyhow::{anyhow, Result};
 use collections::HashMap;
 use command_palette_hooks::CommandInterceptResult;
 use editor::{
-    actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
+    actions::{
+        GoToDiagnostic, GoToPrevDiagnostic, SortLinesCaseInsensitive, SortLinesCaseSensitive,
+    },
     display_map::ToDisplayPoint,
     scroll::Autoscroll,
     Bias, Editor, ToPoint,
@@ -684,11 +686,11 @@ fn generate_commands(_: &AppContext) -> Vec<VimCommand> {
         VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
         VimCommand::new(("cc", ""), editor::actions::Hover),
         VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic).range(wrap_count),
-        VimCommand::new(("cp", "revious"), editor::actions::GoToPrevDiagnostic).range(wrap_count),
-        VimCommand::new(("cN", "ext"), editor::actions::GoToPrevDiagnostic).range(wrap_count),
-        VimCommand::new(("lp", "revious"), editor::actions::GoToPrevDiagnostic).range(wrap_count),
-        VimCommand::new(("lN", "ext"), editor::actions::GoToPrevDiagnostic).range(wrap_count),
+        VimCommand::new(("cn", "ext"), GoToDiagnostic::default()).range(wrap_count),
+        VimCommand::new(("cp", "revious"), GoToPrevDiagnostic::default()).range(wrap_count),
+        VimCommand::new(("cN", "ext"), GoToPrevDiagnostic::default()).range(wrap_count),
+        VimCommand::new(("lp", "revious"), GoToPrevDiagnostic::default()).range(wrap_count),
+        VimCommand::new(("lN", "ext"), GoToPrevDiagnostic::default()).range(wrap_count),
         VimCommand::new(("j", "oin"), JoinLines).range(select_range),
         VimCommand::new(("fo", "ld"), editor::actions::FoldSelectedRanges).range(act_on_range),
         VimCommand::new(("foldo", "pen"), editor::actions::UnfoldLines)
@@ -191,8 +191,8 @@ impl Render for QuickActionBar {
                             .action("Go to Symbol", Box::new(ToggleOutline))
                             .action("Go to Line/Column", Box::new(ToggleGoToLine))
                             .separator()
-                            .action("Next Problem", Box::new(GoToDiagnostic))
-                            .action("Previous Problem", Box::new(GoToPrevDiagnostic))
+                            .action("Next Problem", Box::new(GoToDiagnostic::default()))
+                            .action("Previous Problem", Box::new(GoToPrevDiagnostic::default()))
                             .separator()
                             .action("Next Hunk", Box::new(GoToHunk))
                             .action("Previous Hunk", Box::new(GoToPrevHunk))